@@ -0,0 +1,266 @@
+use core::time::Duration;
+use embedded_hal::i2c::{Error as I2cError, SevenBitAddress};
+use embedded_hal_async::{delay::DelayUs, digital::Wait, i2c::I2c};
+
+use crate::{
+    config::InterruptMasks,
+    error::Error,
+    interrupt::{Event, Events, Interrupts, Status},
+    rail::Rail,
+    ramp::{ramp_target, VoltageRamp},
+    registers::*,
+    TPS_ADDRESS,
+};
+
+/// Async driver for the TPS6572x
+pub struct TPS6572xAsync<I2C, I2CE>
+where
+    I2C: I2c<SevenBitAddress, Error = I2CE>,
+{
+    i2c: I2C,
+    address: SevenBitAddress,
+    masks: Option<InterruptMasks>,
+}
+
+impl<I2C, I2CE> TPS6572xAsync<I2C, I2CE>
+where
+    I2C: I2c<SevenBitAddress, Error = I2CE>,
+    I2CE: I2cError,
+{
+    pub fn new(i2c: I2C) -> Self {
+        Self {
+            i2c,
+            address: TPS_ADDRESS,
+            masks: None,
+        }
+    }
+
+    /// Uses another address than [`TPS_ADDRESS`], e.g. behind an address translator
+    pub fn with_address(mut self, address: SevenBitAddress) -> Self {
+        self.address = address;
+        self
+    }
+
+    /// Address of the chip on the bus
+    pub fn address(&self) -> SevenBitAddress {
+        self.address
+    }
+
+    pub fn release(self) -> I2C {
+        self.i2c
+    }
+
+    pub async fn write_register<R>(&mut self, register: R) -> Result<(), Error<I2CE>>
+    where
+        R: WritableRegister,
+        u8: From<R>,
+    {
+        self.i2c
+            .write(self.address, &[R::ADDRESS as u8, register.into()])
+            .await
+            .map_err(Error::from_i2c)
+    }
+
+    pub async fn read_register<R>(&mut self) -> Result<R, Error<I2CE>>
+    where
+        R: Register + From<u8>,
+    {
+        let mut val: [u8; 1] = [0; 1];
+        self.i2c
+            .write(self.address, &[R::ADDRESS as u8])
+            .await
+            .map_err(Error::from_i2c)?;
+        self.i2c
+            .read(self.address, &mut val)
+            .await
+            .map_err(Error::from_i2c)?;
+        Ok(val[0].into())
+    }
+
+    pub async fn edit_register<R, F>(&mut self, f: F) -> Result<(), Error<I2CE>>
+    where
+        F: FnOnce(R) -> R,
+        R: WritableRegister + From<u8>,
+        u8: From<R>,
+    {
+        let val = self.read_register::<R>().await?;
+        self.write_register(f(val)).await
+    }
+
+    /// Reads, and thus clears, the interrupt flags
+    pub async fn read_interrupts(&mut self) -> Result<Interrupts, Error<I2CE>> {
+        Ok(Interrupts {
+            ir0: self.read_register().await?,
+            ir1: self.read_register().await?,
+            ir2: self.read_register().await?,
+        })
+    }
+
+    /// Reads the status registers
+    pub async fn read_status(&mut self) -> Result<Status, Error<I2CE>> {
+        Ok(Status {
+            charger_status: self.read_register().await?,
+            charger_state: self.read_register().await?,
+            control0: self.read_register().await?,
+            control1: self.read_register().await?,
+            charger_config3: self.read_register().await?,
+        })
+    }
+
+    /// Interrupt masks set with [`TPS6572xAsync::set_interrupt_masks`]
+    pub fn interrupt_masks(&self) -> Option<&InterruptMasks> {
+        self.masks.as_ref()
+    }
+
+    /// Sets the interrupt masks, whose flags are then left out of the events
+    pub async fn set_interrupt_masks(&mut self, masks: InterruptMasks) -> Result<(), Error<I2CE>> {
+        self.write_register(masks.mask0).await?;
+        self.write_register(masks.mask1).await?;
+        self.write_register(masks.mask2).await?;
+        self.masks = Some(masks);
+        Ok(())
+    }
+
+    /// Reads and clears the interrupt flags, returning the events of the unmasked ones
+    ///
+    /// The flags are latched even when masked, only the flags masked with
+    /// [`TPS6572xAsync::set_interrupt_masks`] are left out, as [`crate::config::Configured`] does.
+    pub async fn pending_events(&mut self) -> Result<Events, Error<I2CE>> {
+        let interrupts = self.read_interrupts().await?;
+        let status = self.read_status().await?;
+        Ok(Events::unmasked(interrupts, status, self.masks.as_ref()))
+    }
+
+    /// Output voltage of the rail
+    pub async fn rail_voltage(&mut self, rail: Rail) -> Result<OutputVoltage, Error<I2CE>> {
+        Ok(match rail {
+            Rail::Dcdc1 => self.read_register::<DCDCSetting>().await?.output_voltage(),
+            Rail::Ldo1 => self.read_register::<LDOControl>().await?.output_voltage(),
+        })
+    }
+
+    /// Sets the output voltage of the rail
+    pub async fn set_rail_voltage(
+        &mut self,
+        rail: Rail,
+        voltage: OutputVoltage,
+    ) -> Result<(), Error<I2CE>> {
+        match rail {
+            Rail::Dcdc1 => {
+                self.edit_register(|r: DCDCSetting| r.with_output_voltage(voltage))
+                    .await
+            }
+            Rail::Ldo1 => {
+                self.edit_register(|r: LDOControl| r.with_output_voltage(voltage))
+                    .await
+            }
+        }
+    }
+
+    /// Whether the rail is in regulation
+    pub async fn is_power_good(&mut self, rail: Rail) -> Result<bool, Error<I2CE>> {
+        Ok(rail.power_good(self.read_register().await?))
+    }
+
+    /// Ramps the DCDC1 output to the millivolts, see [`TPS6572xAsync::ramp_rail_to`]
+    pub async fn ramp_dcdc1_to<D>(
+        &mut self,
+        millivolts: u16,
+        step_mv: u16,
+        step_delay: Duration,
+        delay: &mut D,
+    ) -> Result<OutputVoltage, Error<I2CE>>
+    where
+        D: DelayUs,
+    {
+        self.ramp_rail_to(Rail::Dcdc1, millivolts, step_mv, step_delay, delay)
+            .await
+    }
+
+    /// Ramps the LDO1 output to the millivolts, see [`TPS6572xAsync::ramp_rail_to`]
+    pub async fn ramp_ldo1_to<D>(
+        &mut self,
+        millivolts: u16,
+        step_mv: u16,
+        step_delay: Duration,
+        delay: &mut D,
+    ) -> Result<OutputVoltage, Error<I2CE>>
+    where
+        D: DelayUs,
+    {
+        self.ramp_rail_to(Rail::Ldo1, millivolts, step_mv, step_delay, delay)
+            .await
+    }
+
+    /// Ramps the output of the rail to the millivolts, as [`crate::TPS6572x::ramp_rail_to`]
+    pub async fn ramp_rail_to<D>(
+        &mut self,
+        rail: Rail,
+        millivolts: u16,
+        step_mv: u16,
+        step_delay: Duration,
+        delay: &mut D,
+    ) -> Result<OutputVoltage, Error<I2CE>>
+    where
+        D: DelayUs,
+    {
+        let target = ramp_target(millivolts, step_mv)?;
+
+        let check_power_good = self.is_power_good(rail).await?;
+        let from = self.rail_voltage(rail).await?;
+        let step_us = u32::try_from(step_delay.as_micros()).unwrap_or(u32::MAX);
+        for voltage in VoltageRamp::new(from, target, step_mv) {
+            self.set_rail_voltage(rail, voltage).await?;
+            delay.delay_us(step_us).await.map_err(|_| Error::Delay)?;
+
+            if check_power_good && !self.is_power_good(rail).await? {
+                return Err(Error::PowerGoodLost { rail, voltage });
+            }
+        }
+        Ok(target)
+    }
+
+    /// Events signaled on the INT pin
+    pub fn events<'a, P>(&'a mut self, int_pin: &'a mut P) -> EventStream<'a, I2C, I2CE, P>
+    where
+        P: Wait,
+    {
+        EventStream {
+            tps: self,
+            int_pin,
+            pending: None,
+        }
+    }
+}
+
+/// Events signaled on the INT pin, one at a time
+///
+/// The events raised together are queued until taken. INT stays low until the flags are
+/// read, so the flags are read again while it is low, including flags latched while the
+/// previous ones were being read.
+pub struct EventStream<'a, I2C, I2CE, P>
+where
+    I2C: I2c<SevenBitAddress, Error = I2CE>,
+{
+    tps: &'a mut TPS6572xAsync<I2C, I2CE>,
+    int_pin: &'a mut P,
+    pending: Option<Events>,
+}
+
+impl<'a, I2C, I2CE, P> EventStream<'a, I2C, I2CE, P>
+where
+    I2C: I2c<SevenBitAddress, Error = I2CE>,
+    I2CE: I2cError,
+    P: Wait,
+{
+    /// Next event, waiting for INT to be low if none is queued
+    pub async fn next_event(&mut self) -> Result<Event, Error<I2CE>> {
+        loop {
+            if let Some(event) = self.pending.as_mut().and_then(Iterator::next) {
+                return Ok(event);
+            }
+            self.int_pin.wait_for_low().await.map_err(|_| Error::Pin)?;
+            self.pending = Some(self.tps.pending_events().await?);
+        }
+    }
+}
@@ -0,0 +1,35 @@
+use embedded_hal::i2c::{blocking::I2c, Error as I2cError, SevenBitAddress};
+
+use crate::{error::Error, registers::*, TPS6572x};
+
+impl<I2C, I2CE> TPS6572x<I2C, I2CE>
+where
+    I2C: I2c<SevenBitAddress, Error = I2CE>,
+    I2CE: I2cError,
+{
+    /// Enables the low battery comparator at the threshold nearest to the millivolts
+    ///
+    /// The comparator interrupt is unmasked, so crossing the threshold asserts INT and
+    /// is reported as a `LowBattery` event. Returns the selected threshold.
+    pub fn configure_low_battery_alarm(
+        &mut self,
+        millivolts: u16,
+    ) -> Result<BatteryVoltageTreshold, Error<I2CE>> {
+        let threshold = BatteryVoltageTreshold::nearest(millivolts);
+        self.edit_register(|r: ChargerConfig3| r.with_vbatt_treshold(threshold))?;
+        self.edit_register(|r: ChargerConfig2| r.with_batt_voltage_comparator_enabled(true))?;
+        self.edit_register(|r: InterruptMask0| r.with_batt_voltage_comparator_interrupt(false))?;
+        Ok(threshold)
+    }
+
+    /// Disables the low battery comparator and masks its interrupt
+    pub fn disable_low_battery_alarm(&mut self) -> Result<(), Error<I2CE>> {
+        self.edit_register(|r: InterruptMask0| r.with_batt_voltage_comparator_interrupt(true))?;
+        self.edit_register(|r: ChargerConfig2| r.with_batt_voltage_comparator_enabled(false))
+    }
+
+    /// Whether the battery voltage is below the threshold of the comparator, VBAT_COMP
+    pub fn is_battery_low(&mut self) -> Result<bool, Error<I2CE>> {
+        Ok(self.read_register::<ChargerConfig3>()?.vbatt_comparator())
+    }
+}
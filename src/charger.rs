@@ -0,0 +1,217 @@
+use core::time::Duration;
+use embedded_hal::{
+    delay::blocking::DelayUs,
+    i2c::{blocking::I2c, Error as I2cError, SevenBitAddress},
+};
+
+use crate::{
+    delay_for,
+    error::Error,
+    registers::{ChargerConfig0, ChargerState, ChargerStatus},
+    TPS6572x,
+};
+
+/// Time for the charger state machine to restart after toggling CH_EN
+const RESTART_DELAY: Duration = Duration::from_millis(10);
+
+/// Phase of the charger state machine
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum ChargePhase {
+    /// No input power, CH_SLEEP
+    Sleep,
+
+    /// Charger restarting, CH_RESET
+    Reset,
+
+    /// Not charging, e.g. disabled or charge terminated, CH_IDLE
+    Idle,
+
+    /// Deeply discharged battery charged with a reduced current, CH_PRECH
+    Precharge,
+
+    /// Constant current or constant voltage charge, CH_CC_CV
+    FastCharge,
+
+    /// Charger in LDO mode, CH_LDO
+    Ldo,
+
+    /// Charger suspended, e.g. battery temperature out of the window, CH_SUSP
+    Suspended,
+
+    /// Charger stopped on a fault, CH_FAULT
+    Fault,
+
+    /// No state bit set
+    Unknown,
+}
+
+impl ChargePhase {
+    /// Whether the battery is being charged
+    pub fn is_charging(self) -> bool {
+        matches!(self, ChargePhase::Precharge | ChargePhase::FastCharge)
+    }
+}
+
+impl From<ChargerState> for ChargePhase {
+    fn from(state: ChargerState) -> Self {
+        if state.fault() {
+            ChargePhase::Fault
+        } else if state.suspended() {
+            ChargePhase::Suspended
+        } else if state.sleep() {
+            ChargePhase::Sleep
+        } else if state.reset() {
+            ChargePhase::Reset
+        } else if state.precharge() {
+            ChargePhase::Precharge
+        } else if state.constant_current() {
+            ChargePhase::FastCharge
+        } else if state.ldo() {
+            ChargePhase::Ldo
+        } else if state.idle() {
+            ChargePhase::Idle
+        } else {
+            ChargePhase::Unknown
+        }
+    }
+}
+
+/// Likely cause of a stopped charge
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum ChargerFault {
+    /// Fast charge did not complete before the safety timer expired
+    SafetyTimerExpired,
+
+    /// Battery still deeply discharged when the precharge timer expired
+    PrechargeTimeout,
+
+    /// Input over voltage
+    OverVoltage,
+
+    /// Battery temperature out of the charge window
+    TemperatureOut,
+
+    /// Input power not good
+    InputLost,
+
+    /// Charger stopped without a known cause
+    Unknown,
+}
+
+impl<I2C, I2CE> TPS6572x<I2C, I2CE>
+where
+    I2C: I2c<SevenBitAddress, Error = I2CE>,
+    I2CE: I2cError,
+{
+    /// Phase of the charger state machine
+    pub fn charge_phase(&mut self) -> Result<ChargePhase, Error<I2CE>> {
+        Ok(self.read_register::<ChargerState>()?.into())
+    }
+
+    /// Likely cause of the charge being stopped, `None` if the charger is not faulted or suspended
+    ///
+    /// The chip doesn't tell which timer expired, they are told apart with the last phase
+    /// seen before the fault, e.g. from [`ChargePhase::from`] or the charger state events.
+    /// Without it, a timer fault is [`ChargerFault::Unknown`].
+    pub fn diagnose_charger(
+        &mut self,
+        last_phase: Option<ChargePhase>,
+    ) -> Result<Option<ChargerFault>, Error<I2CE>> {
+        let state = self.read_register::<ChargerState>()?;
+        let status = self.read_register::<ChargerStatus>()?;
+        if !state.fault() && !state.suspended() && !status.over_voltage_protection() {
+            return Ok(None);
+        }
+
+        let fault = if status.over_voltage_protection() {
+            ChargerFault::OverVoltage
+        } else if !status.power_source_ok() {
+            ChargerFault::InputLost
+        } else if status.temp_low() || status.temp_high() {
+            ChargerFault::TemperatureOut
+        } else if state.fault() {
+            match last_phase {
+                Some(ChargePhase::Precharge) => ChargerFault::PrechargeTimeout,
+                Some(ChargePhase::FastCharge) | Some(ChargePhase::Ldo) => {
+                    ChargerFault::SafetyTimerExpired
+                }
+                _ => ChargerFault::Unknown,
+            }
+        } else {
+            ChargerFault::Unknown
+        };
+        Ok(Some(fault))
+    }
+
+    /// Restarts a charge stopped by a timer, toggling CH_EN which resets the timers
+    ///
+    /// Fails with [`Error::InvalidState`] when the cause of the fault is still present, e.g.
+    /// no input power or battery temperature out of the window, and with
+    /// [`Error::VerifyFailed`] if the charger is still faulted after the restart.
+    pub fn recover_charger<D>(&mut self, delay: &mut D) -> Result<(), Error<I2CE>>
+    where
+        D: DelayUs,
+    {
+        match self.diagnose_charger(None)? {
+            None => return Ok(()),
+            Some(ChargerFault::OverVoltage)
+            | Some(ChargerFault::InputLost)
+            | Some(ChargerFault::TemperatureOut) => return Err(Error::InvalidState),
+            Some(_) => {}
+        }
+
+        let config0 = self.read_register::<ChargerConfig0>()?;
+        self.write_register(config0.with_charger_enabled(false))?;
+        self.write_register(config0.with_charger_enabled(true))?;
+        delay_for(delay, RESTART_DELAY)?;
+
+        if self.read_register::<ChargerState>()?.fault() {
+            return Err(Error::VerifyFailed);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SUSPENDED: u8 = 1 << 0;
+    const FAULT: u8 = 1 << 1;
+    const LDO: u8 = 1 << 2;
+    const CC_CV: u8 = 1 << 3;
+    const PRECHARGE: u8 = 1 << 4;
+    const IDLE: u8 = 1 << 5;
+    const RESET: u8 = 1 << 6;
+    const SLEEP: u8 = 1 << 7;
+
+    fn phase(bits: u8) -> ChargePhase {
+        ChargerState::from(bits).into()
+    }
+
+    #[test]
+    fn phase_of_a_single_state() {
+        assert_eq!(phase(0), ChargePhase::Unknown);
+        assert_eq!(phase(SUSPENDED), ChargePhase::Suspended);
+        assert_eq!(phase(FAULT), ChargePhase::Fault);
+        assert_eq!(phase(LDO), ChargePhase::Ldo);
+        assert_eq!(phase(CC_CV), ChargePhase::FastCharge);
+        assert_eq!(phase(PRECHARGE), ChargePhase::Precharge);
+        assert_eq!(phase(IDLE), ChargePhase::Idle);
+        assert_eq!(phase(RESET), ChargePhase::Reset);
+        assert_eq!(phase(SLEEP), ChargePhase::Sleep);
+    }
+
+    #[test]
+    fn phase_priority() {
+        assert_eq!(phase(0xFF), ChargePhase::Fault);
+        assert_eq!(phase(SUSPENDED | SLEEP | CC_CV), ChargePhase::Suspended);
+        assert_eq!(phase(SLEEP | RESET | IDLE), ChargePhase::Sleep);
+        assert_eq!(phase(RESET | PRECHARGE), ChargePhase::Reset);
+        assert_eq!(phase(PRECHARGE | CC_CV), ChargePhase::Precharge);
+        assert_eq!(phase(CC_CV | LDO), ChargePhase::FastCharge);
+        assert_eq!(phase(LDO | IDLE), ChargePhase::Ldo);
+    }
+}
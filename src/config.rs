@@ -0,0 +1,635 @@
+use core::{
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+    time::Duration,
+};
+use embedded_hal::i2c::{blocking::I2c, Error as I2cError, SevenBitAddress};
+
+use crate::{
+    error::Error,
+    interrupt::{Events, Interrupts},
+    registers::*,
+    reset::reset_delay,
+    timers::ChargeTimers,
+    TPS6572x,
+};
+
+/// Charger settings of the initial configuration
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct ChargerConfig {
+    pub enabled: bool,
+    pub sys_voltage: SysOutputVoltage,
+    pub input_current: AcInputCurrent,
+    pub charge_voltage: ChargeVoltage,
+    pub charge_current: ChargeCurrentFactor,
+    pub timers: ChargeTimers,
+}
+
+/// Rail settings of the initial configuration
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct RailsConfig {
+    pub dcdc1: OutputVoltage,
+    pub ldo1: OutputVoltage,
+
+    /// Delay of the reset output, 11 ms or 90 ms
+    pub reset_delay: Duration,
+}
+
+/// GPIO settings of the initial configuration
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct GpioConfig {
+    pub pull: GPIOSSC,
+    pub config: GPIOConfig,
+}
+
+/// Interrupt masks of the initial configuration, a set bit masks the interrupt
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct InterruptMasks {
+    pub mask0: InterruptMask0,
+    pub mask1: InterruptMask1,
+    pub mask2: InterruptMask2,
+}
+
+impl InterruptMasks {
+    /// Interrupts without the masked flags
+    pub fn unmasked(&self, interrupts: Interrupts) -> Interrupts {
+        let masked = u32::from(u8::from(self.mask0))
+            | u32::from(u8::from(self.mask1)) << 8
+            | u32::from(u8::from(self.mask2)) << 16;
+        Interrupts::from_bits(interrupts.bits() & !masked)
+    }
+}
+
+/// Initial configuration, `None` keeping the settings of the chip
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub struct Config {
+    pub charger: Option<ChargerConfig>,
+    pub rails: Option<RailsConfig>,
+    pub gpio: Option<GpioConfig>,
+    pub interrupts: Option<InterruptMasks>,
+}
+
+impl Config {
+    /// Checks the values and the constraints between the registers
+    ///
+    /// Fails with [`Error::ConflictingSettings`] if the charger is enabled with the USB input
+    /// suspended, if a GPIO LED driver is on an input, or if the interrupt of a GPIO output
+    /// is unmasked.
+    pub fn validate<I2CE>(&self) -> Result<(), Error<I2CE>> {
+        if let Some(charger) = &self.charger {
+            charger.timers.timers()?;
+            if charger.enabled && charger.input_current == AcInputCurrent::UsbSuspend {
+                return Err(Error::ConflictingSettings);
+            }
+        }
+
+        if let Some(rails) = &self.rails {
+            reset_delay(rails.reset_delay)?;
+        }
+
+        if let Some(gpio) = &self.gpio {
+            let config = gpio.config;
+            let led_on_input =
+                |mode, drive| mode == GPIOMode::Input && drive == GPIODrive::LedDriver5mA;
+            if led_on_input(config.gpio2(), config.gpio2_led())
+                || led_on_input(config.gpio3(), config.gpio3_led())
+            {
+                return Err(Error::ConflictingSettings);
+            }
+
+            if let Some(interrupts) = &self.interrupts {
+                let mask = interrupts.mask2;
+                let unmasked_output = [
+                    (config.gpio0(), mask.gpio0_interrupt()),
+                    (config.gpio1(), mask.gpio1_interrupt()),
+                    (config.gpio2(), mask.gpio2_interrupt()),
+                    (config.gpio3(), mask.gpio3_interrupt()),
+                ]
+                .into_iter()
+                .any(|(mode, masked)| mode == GPIOMode::Ouput && !masked);
+                if unmasked_output {
+                    return Err(Error::ConflictingSettings);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Steps of the [`Configure`] builder
+pub mod step {
+    pub struct Charger;
+    pub struct Rails;
+    pub struct Gpio;
+    pub struct Interrupts;
+    pub struct Ready;
+}
+
+/// Builder of the initial configuration, going through each step in order
+///
+/// ```
+/// use embedded_hal::i2c::{blocking::I2c, Error, SevenBitAddress};
+/// use tps6572x::{config::Configured, TPS6572x};
+///
+/// fn init<I2C, E>(i2c: I2C) -> Result<Configured<I2C, E>, tps6572x::error::Error<E>>
+/// where
+///     I2C: I2c<SevenBitAddress, Error = E>,
+///     E: Error,
+/// {
+///     TPS6572x::new(i2c)
+///         .configure()
+///         .charger(None)
+///         .rails(None)
+///         .gpio(None)
+///         .interrupts(None)
+///         .apply()
+/// }
+/// ```
+pub struct Configure<I2C, I2CE, S>
+where
+    I2C: I2c<SevenBitAddress, Error = I2CE>,
+{
+    tps: TPS6572x<I2C, I2CE>,
+    config: Config,
+    step: PhantomData<S>,
+}
+
+impl<I2C, I2CE> TPS6572x<I2C, I2CE>
+where
+    I2C: I2c<SevenBitAddress, Error = I2CE>,
+    I2CE: I2cError,
+{
+    /// Starts the initial configuration of the chip
+    pub fn configure(self) -> Configure<I2C, I2CE, step::Charger> {
+        Configure {
+            tps: self,
+            config: Config::default(),
+            step: PhantomData,
+        }
+    }
+}
+
+impl<I2C, I2CE, S> Configure<I2C, I2CE, S>
+where
+    I2C: I2c<SevenBitAddress, Error = I2CE>,
+    I2CE: I2cError,
+{
+    fn next<N>(self, f: impl FnOnce(&mut Config)) -> Configure<I2C, I2CE, N> {
+        let mut config = self.config;
+        f(&mut config);
+        Configure {
+            tps: self.tps,
+            config,
+            step: PhantomData,
+        }
+    }
+}
+
+impl<I2C, I2CE> Configure<I2C, I2CE, step::Charger>
+where
+    I2C: I2c<SevenBitAddress, Error = I2CE>,
+    I2CE: I2cError,
+{
+    pub fn charger(self, charger: Option<ChargerConfig>) -> Configure<I2C, I2CE, step::Rails> {
+        self.next(|c| c.charger = charger)
+    }
+}
+
+impl<I2C, I2CE> Configure<I2C, I2CE, step::Rails>
+where
+    I2C: I2c<SevenBitAddress, Error = I2CE>,
+    I2CE: I2cError,
+{
+    pub fn rails(self, rails: Option<RailsConfig>) -> Configure<I2C, I2CE, step::Gpio> {
+        self.next(|c| c.rails = rails)
+    }
+}
+
+impl<I2C, I2CE> Configure<I2C, I2CE, step::Gpio>
+where
+    I2C: I2c<SevenBitAddress, Error = I2CE>,
+    I2CE: I2cError,
+{
+    pub fn gpio(self, gpio: Option<GpioConfig>) -> Configure<I2C, I2CE, step::Interrupts> {
+        self.next(|c| c.gpio = gpio)
+    }
+}
+
+impl<I2C, I2CE> Configure<I2C, I2CE, step::Interrupts>
+where
+    I2C: I2c<SevenBitAddress, Error = I2CE>,
+    I2CE: I2cError,
+{
+    pub fn interrupts(
+        self,
+        interrupts: Option<InterruptMasks>,
+    ) -> Configure<I2C, I2CE, step::Ready> {
+        self.next(|c| c.interrupts = interrupts)
+    }
+}
+
+impl<I2C, I2CE> Configure<I2C, I2CE, step::Ready>
+where
+    I2C: I2c<SevenBitAddress, Error = I2CE>,
+    I2CE: I2cError,
+{
+    /// Configuration to apply
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Validates the configuration and writes it to the chip
+    ///
+    /// Nothing is written if the validation fails.
+    pub fn apply(self) -> Result<Configured<I2C, I2CE>, Error<I2CE>> {
+        let mut configured = Configured {
+            tps: self.tps,
+            config: self.config,
+        };
+        configured.reapply()?;
+        Ok(configured)
+    }
+}
+
+/// Driver of a chip with a known configuration
+///
+/// Derefs to the [`TPS6572x`] for the other operations.
+pub struct Configured<I2C, I2CE>
+where
+    I2C: I2c<SevenBitAddress, Error = I2CE>,
+{
+    tps: TPS6572x<I2C, I2CE>,
+    config: Config,
+}
+
+impl<I2C, I2CE> Configured<I2C, I2CE>
+where
+    I2C: I2c<SevenBitAddress, Error = I2CE>,
+    I2CE: I2cError,
+{
+    /// Applied configuration
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    pub fn release(self) -> TPS6572x<I2C, I2CE> {
+        self.tps
+    }
+
+    /// Reads and clears the interrupt flags, returning the events of the unmasked ones
+    ///
+    /// Same as [`TPS6572x::pending_events`], without the flags masked by the configuration.
+    pub fn pending_events(&mut self) -> Result<Events, Error<I2CE>> {
+        let interrupts = self.tps.read_interrupts()?;
+        let status = self.tps.read_status()?;
+        Ok(Events::unmasked(
+            interrupts,
+            status,
+            self.config.interrupts.as_ref(),
+        ))
+    }
+
+    /// Writes the configuration again, e.g. after the chip was reset
+    ///
+    /// The charger is disabled while its settings change, the GPIO levels are set before
+    /// their direction and the stale interrupt flags are cleared before the masks are set.
+    pub fn reapply(&mut self) -> Result<(), Error<I2CE>> {
+        let config = self.config;
+        config.validate()?;
+        let tps = &mut self.tps;
+
+        if let Some(charger) = config.charger {
+            let (precharge, safety) = charger.timers.timers()?;
+            tps.edit_register(|r: ChargerConfig0| r.with_charger_enabled(false))?;
+            tps.edit_register(|r: ChargerConfig3| r.with_charge_voltage(charger.charge_voltage))?;
+            tps.edit_register(|r: ChargerConfig2| {
+                r.with_pre_charge_timer(precharge)
+                    .with_safety_charge_timer(safety)
+            })?;
+            tps.edit_register(|r: ChargerConfig1| {
+                r.with_charge_current_factor(charger.charge_current)
+            })?;
+            tps.edit_register(|r: ChargerConfig0| {
+                r.with_output_voltage(charger.sys_voltage)
+                    .with_ac_input_current(charger.input_current)
+                    .with_dynamic_timer(charger.timers.dynamic_timer)
+                    .with_thermal_loop(charger.timers.thermal_regulation)
+                    .with_charger_enabled(charger.enabled)
+            })?;
+        }
+
+        if let Some(rails) = config.rails {
+            let delay = reset_delay(rails.reset_delay)?;
+            tps.edit_register(|r: DCDCSetting| r.with_output_voltage(rails.dcdc1))?;
+            tps.edit_register(|r: LDOControl| r.with_output_voltage(rails.ldo1))?;
+            tps.edit_register(|r: Control1| r.with_reset_delay(delay))?;
+        }
+
+        if let Some(gpio) = config.gpio {
+            tps.write_register(gpio.pull)?;
+            tps.write_register(gpio.config)?;
+        }
+
+        if let Some(interrupts) = config.interrupts {
+            tps.read_interrupts()?;
+            tps.write_register(interrupts.mask0)?;
+            tps.write_register(interrupts.mask1)?;
+            tps.write_register(interrupts.mask2)?;
+        }
+        Ok(())
+    }
+
+    /// Whether the registers still hold the configuration
+    pub fn verify(&mut self) -> Result<bool, Error<I2CE>> {
+        let config = self.config;
+        let tps = &mut self.tps;
+
+        if let Some(charger) = config.charger {
+            let config0 = tps.read_register::<ChargerConfig0>()?;
+            let matches = config0.charger_enabled() == charger.enabled
+                && config0.output_voltage() == charger.sys_voltage
+                && config0.ac_input_current() == charger.input_current
+                && tps
+                    .read_register::<ChargerConfig1>()?
+                    .charge_current_factor()
+                    == charger.charge_current
+                && tps.read_register::<ChargerConfig3>()?.charge_voltage()
+                    == charger.charge_voltage
+                && tps.charge_timers()? == charger.timers;
+            if !matches {
+                return Ok(false);
+            }
+        }
+
+        if let Some(rails) = config.rails {
+            let matches = tps.read_register::<DCDCSetting>()?.output_voltage() == rails.dcdc1
+                && tps.read_register::<LDOControl>()?.output_voltage() == rails.ldo1
+                && tps.reset_delay()? == rails.reset_delay;
+            if !matches {
+                return Ok(false);
+            }
+        }
+
+        if let Some(gpio) = config.gpio {
+            if tps.read_register::<GPIOSSC>()? != gpio.pull
+                || tps.read_register::<GPIOConfig>()? != gpio.config
+            {
+                return Ok(false);
+            }
+        }
+
+        if let Some(interrupts) = config.interrupts {
+            let masks = InterruptMasks {
+                mask0: tps.read_register()?,
+                mask1: tps.read_register()?,
+                mask2: tps.read_register()?,
+            };
+            if masks != interrupts {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+}
+
+impl<I2C, I2CE> Deref for Configured<I2C, I2CE>
+where
+    I2C: I2c<SevenBitAddress, Error = I2CE>,
+{
+    type Target = TPS6572x<I2C, I2CE>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.tps
+    }
+}
+
+impl<I2C, I2CE> DerefMut for Configured<I2C, I2CE>
+where
+    I2C: I2c<SevenBitAddress, Error = I2CE>,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.tps
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec::Vec;
+
+    use super::*;
+    use crate::mock::{BusError, Chip};
+
+    const CHARGER: ChargerConfig = ChargerConfig {
+        enabled: true,
+        sys_voltage: SysOutputVoltage::V4_4,
+        input_current: AcInputCurrent::MA500,
+        charge_voltage: ChargeVoltage::V4_200,
+        charge_current: ChargeCurrentFactor::P100,
+        timers: ChargeTimers {
+            precharge: Duration::from_secs(30 * 60),
+            safety: Duration::from_secs(5 * 60 * 60),
+            dynamic_timer: true,
+            thermal_regulation: true,
+        },
+    };
+
+    const RAILS: RailsConfig = RailsConfig {
+        dcdc1: OutputVoltage::V1_800,
+        ldo1: OutputVoltage::V3_300,
+        reset_delay: Duration::from_millis(90),
+    };
+
+    fn gpio() -> GpioConfig {
+        GpioConfig {
+            pull: GPIOSSC::from(0b1111),
+            config: GPIOConfig::from(0b0011),
+        }
+    }
+
+    fn interrupts() -> InterruptMasks {
+        InterruptMasks {
+            mask0: InterruptMask0::from(0),
+            mask1: InterruptMask1::from(0xFF),
+            mask2: InterruptMask2::from(0b1100_0000),
+        }
+    }
+
+    fn config() -> Config {
+        Config {
+            charger: Some(CHARGER),
+            rails: Some(RAILS),
+            gpio: Some(gpio()),
+            interrupts: Some(interrupts()),
+        }
+    }
+
+    fn apply(chip: &mut Chip, config: Config) -> Result<(), Error<BusError>> {
+        TPS6572x::new(chip)
+            .configure()
+            .charger(config.charger)
+            .rails(config.rails)
+            .gpio(config.gpio)
+            .interrupts(config.interrupts)
+            .apply()
+            .map(|_| ())
+    }
+
+    #[test]
+    fn validates_a_consistent_config() {
+        assert!(config().validate::<()>().is_ok());
+        assert!(Config::default().validate::<()>().is_ok());
+    }
+
+    #[test]
+    fn rejects_the_charger_enabled_with_the_usb_input_suspended() {
+        let mut charger = CHARGER;
+        charger.input_current = AcInputCurrent::UsbSuspend;
+        let config = Config {
+            charger: Some(charger),
+            ..config()
+        };
+        assert!(matches!(
+            config.validate::<()>(),
+            Err(Error::ConflictingSettings)
+        ));
+
+        charger.enabled = false;
+        let config = Config {
+            charger: Some(charger),
+            ..config
+        };
+        assert!(config.validate::<()>().is_ok());
+    }
+
+    #[test]
+    fn rejects_unsupported_values() {
+        let mut charger = CHARGER;
+        charger.timers.safety = Duration::from_secs(7 * 60 * 60);
+        let config = Config {
+            charger: Some(charger),
+            ..config()
+        };
+        assert!(matches!(
+            config.validate::<()>(),
+            Err(Error::UnsupportedValue { .. })
+        ));
+
+        let config = Config {
+            rails: Some(RailsConfig {
+                reset_delay: Duration::from_millis(50),
+                ..RAILS
+            }),
+            ..Config::default()
+        };
+        assert!(matches!(
+            config.validate::<()>(),
+            Err(Error::UnsupportedValue { .. })
+        ));
+    }
+
+    #[test]
+    fn rejects_conflicting_gpio_settings() {
+        let led_on_input = Config {
+            gpio: Some(GpioConfig {
+                config: gpio()
+                    .config
+                    .with_gpio2(GPIOMode::Input)
+                    .with_gpio2_led(GPIODrive::LedDriver5mA),
+                ..gpio()
+            }),
+            ..config()
+        };
+        assert!(matches!(
+            led_on_input.validate::<()>(),
+            Err(Error::ConflictingSettings)
+        ));
+
+        let unmasked_output = Config {
+            interrupts: Some(InterruptMasks {
+                mask2: InterruptMask2::from(0),
+                ..interrupts()
+            }),
+            ..config()
+        };
+        assert!(matches!(
+            unmasked_output.validate::<()>(),
+            Err(Error::ConflictingSettings)
+        ));
+
+        let gpio_only = Config {
+            interrupts: None,
+            ..unmasked_output
+        };
+        assert!(gpio_only.validate::<()>().is_ok());
+    }
+
+    #[test]
+    fn writes_nothing_when_the_validation_fails() {
+        let mut chip = Chip::default();
+        let mut charger = CHARGER;
+        charger.input_current = AcInputCurrent::UsbSuspend;
+        let config = Config {
+            charger: Some(charger),
+            ..config()
+        };
+        assert!(apply(&mut chip, config).is_err());
+        assert!(chip.writes.is_empty());
+    }
+
+    #[test]
+    fn writes_in_a_safe_order() {
+        let mut chip = Chip::default();
+        chip.registers[RegisterAddress::ChargerConfig0 as usize] = 0b1;
+        apply(&mut chip, config()).unwrap();
+
+        let addresses: Vec<u8> = chip.writes.iter().map(|(address, _)| *address).collect();
+        let expected = [
+            RegisterAddress::ChargerConfig0,
+            RegisterAddress::ChargerConfig3,
+            RegisterAddress::ChargerConfig2,
+            RegisterAddress::ChargerConfig1,
+            RegisterAddress::ChargerConfig0,
+            RegisterAddress::DCDCSetting,
+            RegisterAddress::LDOControl,
+            RegisterAddress::Control1,
+            RegisterAddress::GPIOSSC,
+            RegisterAddress::GPIOConfig,
+            RegisterAddress::InterruptMask0,
+            RegisterAddress::InterruptMask1,
+            RegisterAddress::InterruptMask2,
+        ]
+        .map(|address| address as u8);
+        assert_eq!(addresses, expected);
+
+        // the charger is disabled while its settings change, and enabled last
+        let charger_enabled = |(_, value): (u8, u8)| value & 0b1 != 0;
+        assert!(!charger_enabled(chip.writes[0]));
+        assert!(charger_enabled(chip.writes[4]));
+    }
+
+    #[test]
+    fn reapply_writes_only_the_configured_steps() {
+        let mut chip = Chip::default();
+        let config = Config {
+            rails: Some(RAILS),
+            ..Config::default()
+        };
+        apply(&mut chip, config).unwrap();
+
+        let addresses: Vec<u8> = chip.writes.iter().map(|(address, _)| *address).collect();
+        let expected = [
+            RegisterAddress::DCDCSetting,
+            RegisterAddress::LDOControl,
+            RegisterAddress::Control1,
+        ]
+        .map(|address| address as u8);
+        assert_eq!(addresses, expected);
+    }
+}
@@ -0,0 +1,123 @@
+use core::cell::{Cell, RefCell};
+use embedded_hal::i2c::{blocking::I2c, Error as I2cError, SevenBitAddress};
+
+use crate::{error::Error, registers::Control0, TPS6572x};
+
+/// Switching mode of DCDC1
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum DcdcMode {
+    /// Automatic PFM/PWM switching, saving power at light load
+    Auto,
+
+    /// Forced PWM, lower ripple and faster load transients
+    ForcedPwm,
+}
+
+impl<I2C, I2CE> TPS6572x<I2C, I2CE>
+where
+    I2C: I2c<SevenBitAddress, Error = I2CE>,
+    I2CE: I2cError,
+{
+    /// Switching mode of DCDC1
+    pub fn dcdc_mode(&mut self) -> Result<DcdcMode, Error<I2CE>> {
+        if self.read_register::<Control0>()?.forced_pwm() {
+            Ok(DcdcMode::ForcedPwm)
+        } else {
+            Ok(DcdcMode::Auto)
+        }
+    }
+
+    /// Sets the switching mode of DCDC1
+    pub fn set_dcdc_mode(&mut self, mode: DcdcMode) -> Result<(), Error<I2CE>> {
+        self.edit_register(|r: Control0| r.with_forced_pwm(mode == DcdcMode::ForcedPwm))
+    }
+}
+
+/// Temporary requests of forced PWM from subsystems, e.g. during radio TX or ADC sampling
+///
+/// Each request returns a [`ForcedPwmGuard`], forced PWM is set with the first request
+/// and cleared only when the last guard is dropped.
+pub struct LoadHint<'a, I2C, I2CE>
+where
+    I2C: I2c<SevenBitAddress, Error = I2CE>,
+    I2CE: I2cError,
+{
+    tps: &'a RefCell<TPS6572x<I2C, I2CE>>,
+    count: Cell<u8>,
+    release_pending: Cell<bool>,
+}
+
+impl<'a, I2C, I2CE> LoadHint<'a, I2C, I2CE>
+where
+    I2C: I2c<SevenBitAddress, Error = I2CE>,
+    I2CE: I2cError,
+{
+    pub fn new(tps: &'a RefCell<TPS6572x<I2C, I2CE>>) -> Self {
+        Self {
+            tps,
+            count: Cell::new(0),
+            release_pending: Cell::new(false),
+        }
+    }
+
+    /// Forces PWM until the returned guard is dropped
+    ///
+    /// Fails with [`Error::InvalidState`] if the driver is already borrowed.
+    pub fn request(&self) -> Result<ForcedPwmGuard<'_, 'a, I2C, I2CE>, Error<I2CE>> {
+        let count = self.count.get().checked_add(1).ok_or(Error::InvalidState)?;
+        if count == 1 {
+            self.set_mode(DcdcMode::ForcedPwm)?;
+        }
+        self.count.set(count);
+        Ok(ForcedPwmGuard { hint: self })
+    }
+
+    /// Number of guards alive
+    pub fn active(&self) -> u8 {
+        self.count.get()
+    }
+
+    /// Clears forced PWM if it could not be cleared when the last guard was dropped
+    pub fn sync(&self) -> Result<(), Error<I2CE>> {
+        if self.release_pending.get() && self.count.get() == 0 {
+            self.set_mode(DcdcMode::Auto)?;
+        }
+        Ok(())
+    }
+
+    fn set_mode(&self, mode: DcdcMode) -> Result<(), Error<I2CE>> {
+        self.tps
+            .try_borrow_mut()
+            .map_err(|_| Error::InvalidState)?
+            .set_dcdc_mode(mode)?;
+        self.release_pending.set(false);
+        Ok(())
+    }
+}
+
+/// Keeps DCDC1 in forced PWM while alive
+///
+/// Errors when clearing forced PWM on drop can't be reported, [`LoadHint::sync`] retries it.
+pub struct ForcedPwmGuard<'h, 'a, I2C, I2CE>
+where
+    I2C: I2c<SevenBitAddress, Error = I2CE>,
+    I2CE: I2cError,
+{
+    hint: &'h LoadHint<'a, I2C, I2CE>,
+}
+
+impl<'h, 'a, I2C, I2CE> Drop for ForcedPwmGuard<'h, 'a, I2C, I2CE>
+where
+    I2C: I2c<SevenBitAddress, Error = I2CE>,
+    I2CE: I2cError,
+{
+    fn drop(&mut self) {
+        let count = self.hint.count.get() - 1;
+        self.hint.count.set(count);
+        if count == 0 {
+            self.hint.release_pending.set(true);
+            let _ = self.hint.set_mode(DcdcMode::Auto);
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use core::time::Duration;
+use embedded_hal::{
+    delay::blocking::DelayUs,
+    i2c::{blocking::I2c, Error as I2cError, SevenBitAddress},
+};
+
+use crate::{error::Error, rail::Rail, registers::OutputVoltage, TPS6572x};
+
+/// Performance profile of the MCU supplied by DCDC1
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum DvsProfile {
+    Low,
+    Nominal,
+    Turbo,
+}
+
+/// DCDC1 output voltage of each [`DvsProfile`]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct DvsProfiles {
+    pub low: OutputVoltage,
+    pub nominal: OutputVoltage,
+    pub turbo: OutputVoltage,
+
+    /// Longest time for DCDC1 to be in regulation after raising its voltage
+    pub settle_timeout: Duration,
+}
+
+impl DvsProfiles {
+    pub const fn new(low: OutputVoltage, nominal: OutputVoltage, turbo: OutputVoltage) -> Self {
+        Self {
+            low,
+            nominal,
+            turbo,
+            settle_timeout: Duration::from_millis(1),
+        }
+    }
+
+    pub const fn with_settle_timeout(mut self, settle_timeout: Duration) -> Self {
+        self.settle_timeout = settle_timeout;
+        self
+    }
+
+    /// DCDC1 output voltage of the profile
+    pub fn voltage(&self, profile: DvsProfile) -> OutputVoltage {
+        match profile {
+            DvsProfile::Low => self.low,
+            DvsProfile::Nominal => self.nominal,
+            DvsProfile::Turbo => self.turbo,
+        }
+    }
+}
+
+impl<I2C, I2CE> TPS6572x<I2C, I2CE>
+where
+    I2C: I2c<SevenBitAddress, Error = I2CE>,
+    I2CE: I2cError,
+{
+    /// Switches DCDC1 to the voltage of the profile
+    ///
+    /// When raising the voltage, it is set and DCDC1 is back in regulation before
+    /// `change_frequency` is called. When lowering it, `change_frequency` is called first,
+    /// so the MCU never runs at a frequency its supply does not support.
+    pub fn set_dvs_profile<D, F>(
+        &mut self,
+        profiles: &DvsProfiles,
+        profile: DvsProfile,
+        delay: &mut D,
+        change_frequency: F,
+    ) -> Result<(), Error<I2CE>>
+    where
+        D: DelayUs,
+        F: FnOnce(),
+    {
+        let target = profiles.voltage(profile);
+        let current = self.rail_voltage(Rail::Dcdc1)?;
+
+        if target.millivolts() > current.millivolts() {
+            self.set_rail_voltage(Rail::Dcdc1, target)?;
+            self.wait_power_good(Rail::Dcdc1, profiles.settle_timeout, delay)?;
+            change_frequency();
+        } else {
+            change_frequency();
+            self.set_rail_voltage(Rail::Dcdc1, target)?;
+        }
+        Ok(())
+    }
+}
@@ -1,7 +1,91 @@
+use embedded_hal::i2c::{Error as I2cError, ErrorKind, NoAcknowledgeSource};
+
+use crate::{rail::Rail, registers::OutputVoltage};
+
 /// Possible error when reading an register error
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[derive(Debug, Clone, Copy)]
 pub enum Error<I2CError> {
     /// Internal i2c error
     I2c(I2CError),
+
+    /// Value outside of the range supported by the chip
+    OutOfRange { value: i32, min: i32, max: i32 },
+
+    /// Value within the range of the chip but not one of its supported values
+    UnsupportedValue { value: i32 },
+
+    /// Operation not supported by the variant of the chip
+    UnsupportedOnVariant,
+
+    /// Value read back after a write does not match the written value
+    VerifyFailed,
+
+    /// Operation not allowed in the current state of the chip
+    InvalidState,
+
+    /// Settings contradicting each other, e.g. across registers
+    ConflictingSettings,
+
+    /// The expected condition was not reached in time
+    Timeout,
+
+    /// The delay provider failed
+    Delay,
+
+    /// The interrupt pin failed
+    Pin,
+
+    /// The rail dropped out of regulation, the output being set at the voltage
+    PowerGoodLost { rail: Rail, voltage: OutputVoltage },
+
+    /// No TPS6572x answered at the address, with the i2c error if any
+    DeviceNotFound(Option<I2CError>),
+}
+
+impl<E> Error<E> {
+    /// Error for an unsupported value, [`Error::OutOfRange`] if it is outside of the range
+    pub(crate) fn unsupported(value: i32, min: i32, max: i32) -> Self {
+        if (min..=max).contains(&value) {
+            Error::UnsupportedValue { value }
+        } else {
+            Error::OutOfRange { value, min, max }
+        }
+    }
+}
+
+impl<E> Error<E>
+where
+    E: I2cError,
+{
+    /// Maps an i2c error, a NACK on the address meaning no chip answered
+    pub fn from_i2c(err: E) -> Self {
+        match err.kind() {
+            ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address) => {
+                Error::DeviceNotFound(Some(err))
+            }
+            _ => Error::I2c(err),
+        }
+    }
+
+    /// Kind of the underlying i2c error, if any
+    pub fn i2c_kind(&self) -> Option<ErrorKind> {
+        match self {
+            Error::I2c(e) | Error::DeviceNotFound(Some(e)) => Some(e.kind()),
+            _ => None,
+        }
+    }
+
+    /// The chip did not acknowledge its address, it is likely missing or unpowered
+    pub fn is_address_nack(&self) -> bool {
+        matches!(
+            self.i2c_kind(),
+            Some(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address))
+        )
+    }
+
+    /// The chip acknowledged its address but the transfer failed, likely a bus glitch
+    pub fn is_bus_error(&self) -> bool {
+        matches!(self, Error::I2c(_))
+    }
 }
@@ -0,0 +1,370 @@
+use embedded_hal::i2c::{blocking::I2c, Error as I2cError, SevenBitAddress};
+
+use crate::{config::InterruptMasks, error::Error, rail::Rail, registers::*, TPS6572x};
+
+/// Latched interrupt flags, cleared when read
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Interrupts {
+    pub ir0: Interrupt0,
+    pub ir1: Interrupt1,
+    pub ir2: Interrupt2,
+}
+
+impl Interrupts {
+    /// Whether no interrupt flag is set
+    pub fn is_empty(&self) -> bool {
+        self.bits() == 0
+    }
+
+    /// Flags of the status changes between two samples, as if raised by the chip
+    ///
+    /// The GPIO inputs are not part of the status, their flags are never set.
+    pub fn from_changes(previous: &Status, current: &Status) -> Self {
+        let (a, b) = (previous.charger_status, current.charger_status);
+        let flags = [
+            (0, a.thermal_loop_active() != b.thermal_loop_active()),
+            (
+                1,
+                previous.charger_config3.vbatt_comparator()
+                    != current.charger_config3.vbatt_comparator(),
+            ),
+            (2, a.power_source_ok() != b.power_source_ok()),
+            (3, a.charger_active() != b.charger_active()),
+            (
+                5,
+                a.over_voltage_protection() != b.over_voltage_protection(),
+            ),
+            (6, a.temp_low() != b.temp_low()),
+            (7, a.temp_high() != b.temp_high()),
+            (
+                17,
+                previous.control1.push_button_pressed() != current.control1.push_button_pressed(),
+            ),
+            (
+                18,
+                Rail::Ldo1.power_good(previous.control0) != Rail::Ldo1.power_good(current.control0),
+            ),
+            (
+                19,
+                Rail::Dcdc1.power_good(previous.control0)
+                    != Rail::Dcdc1.power_good(current.control0),
+            ),
+        ]
+        .into_iter()
+        .filter(|(_, changed)| *changed)
+        .fold(0, |bits, (bit, _)| bits | 1 << bit);
+
+        let state = u8::from(previous.charger_state) ^ u8::from(current.charger_state);
+        Self::from_bits(flags | u32::from(state) << 8)
+    }
+
+    pub(crate) fn from_bits(bits: u32) -> Self {
+        let [ir0, ir1, ir2, _] = bits.to_le_bytes();
+        Self {
+            ir0: ir0.into(),
+            ir1: ir1.into(),
+            ir2: ir2.into(),
+        }
+    }
+
+    /// IR0 to IR2 as the lowest bytes
+    pub(crate) fn bits(&self) -> u32 {
+        u32::from(u8::from(self.ir0))
+            | u32::from(u8::from(self.ir1)) << 8
+            | u32::from(u8::from(self.ir2)) << 16
+    }
+}
+
+/// Status registers giving the state behind the interrupt flags
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Status {
+    pub charger_status: ChargerStatus,
+    pub charger_state: ChargerState,
+    pub control0: Control0,
+    pub control1: Control1,
+    pub charger_config3: ChargerConfig3,
+}
+
+/// Event of the chip, with the state after the event
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Event {
+    /// Thermal regulation loop of the charger
+    ThermalLoop { active: bool },
+
+    /// Battery voltage crossed the low battery threshold
+    LowBattery { low: bool },
+
+    /// Input power source
+    PowerSource { ok: bool },
+
+    /// Charger activity
+    ChargerActive { active: bool },
+
+    /// Input over voltage protection
+    OverVoltage { active: bool },
+
+    /// Battery too cold to charge
+    TemperatureLow { active: bool },
+
+    /// Battery too hot to charge
+    TemperatureHigh { active: bool },
+
+    /// Charger state machine changed state
+    ChargerState(ChargerState),
+
+    /// Push button
+    PushButton { pressed: bool },
+
+    /// Regulation of a rail
+    PowerGood { rail: Rail, good: bool },
+
+    /// Input change on the GPIO
+    Gpio(u8),
+}
+
+/// Events of the set interrupt flags, in register and bit order
+///
+/// The state changes of the charger state machine are reported as a single event.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Events {
+    flags: u32,
+    status: Status,
+}
+
+impl Events {
+    pub fn new(interrupts: Interrupts, status: Status) -> Self {
+        Self {
+            flags: interrupts.bits(),
+            status,
+        }
+    }
+
+    /// Events of the interrupts, without the flags set in the masks
+    pub(crate) fn unmasked(
+        interrupts: Interrupts,
+        status: Status,
+        masks: Option<&InterruptMasks>,
+    ) -> Self {
+        let interrupts = match masks {
+            Some(masks) => masks.unmasked(interrupts),
+            None => interrupts,
+        };
+        Self::new(interrupts, status)
+    }
+
+    fn event(&self, bit: u32) -> Option<Event> {
+        let charger = self.status.charger_status;
+        Some(match bit {
+            0 => Event::ThermalLoop {
+                active: charger.thermal_loop_active(),
+            },
+            1 => Event::LowBattery {
+                low: self.status.charger_config3.vbatt_comparator(),
+            },
+            2 => Event::PowerSource {
+                ok: charger.power_source_ok(),
+            },
+            3 => Event::ChargerActive {
+                active: charger.charger_active(),
+            },
+            5 => Event::OverVoltage {
+                active: charger.over_voltage_protection(),
+            },
+            6 => Event::TemperatureLow {
+                active: charger.temp_low(),
+            },
+            7 => Event::TemperatureHigh {
+                active: charger.temp_high(),
+            },
+            8..=15 => Event::ChargerState(self.status.charger_state),
+            17 => Event::PushButton {
+                pressed: self.status.control1.push_button_pressed(),
+            },
+            18 => Event::PowerGood {
+                rail: Rail::Ldo1,
+                good: Rail::Ldo1.power_good(self.status.control0),
+            },
+            19 => Event::PowerGood {
+                rail: Rail::Dcdc1,
+                good: Rail::Dcdc1.power_good(self.status.control0),
+            },
+            20..=23 => Event::Gpio((bit - 20) as u8),
+            _ => return None,
+        })
+    }
+}
+
+impl Iterator for Events {
+    type Item = Event;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.flags != 0 {
+            let bit = self.flags.trailing_zeros();
+            self.flags &= !(1 << bit);
+            if (8..=15).contains(&bit) {
+                // IR1 only tells which state changed
+                self.flags &= !0xFF00;
+            }
+
+            if let Some(event) = self.event(bit) {
+                return Some(event);
+            }
+        }
+        None
+    }
+}
+
+impl<I2C, I2CE> TPS6572x<I2C, I2CE>
+where
+    I2C: I2c<SevenBitAddress, Error = I2CE>,
+    I2CE: I2cError,
+{
+    /// Reads, and thus clears, the interrupt flags
+    pub fn read_interrupts(&mut self) -> Result<Interrupts, Error<I2CE>> {
+        Ok(Interrupts {
+            ir0: self.read_register()?,
+            ir1: self.read_register()?,
+            ir2: self.read_register()?,
+        })
+    }
+
+    /// Reads the status registers
+    pub fn read_status(&mut self) -> Result<Status, Error<I2CE>> {
+        Ok(Status {
+            charger_status: self.read_register()?,
+            charger_state: self.read_register()?,
+            control0: self.read_register()?,
+            control1: self.read_register()?,
+            charger_config3: self.read_register()?,
+        })
+    }
+
+    /// Reads and clears the interrupt flags, returning their events
+    ///
+    /// Usually called when the INT pin is asserted.
+    pub fn pending_events(&mut self) -> Result<Events, Error<I2CE>> {
+        let interrupts = self.read_interrupts()?;
+        let status = self.read_status()?;
+        Ok(Events::new(interrupts, status))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::{vec, vec::Vec};
+
+    use super::*;
+
+    const IDLE: u8 = 1 << 5;
+    const FAST_CHARGE: u8 = 1 << 3;
+
+    fn status(charger_status: u8, charger_state: u8, control0: u8, control1: u8) -> Status {
+        Status {
+            charger_status: ChargerStatus::from(charger_status),
+            charger_state: ChargerState::from(charger_state),
+            control0: Control0::from(control0),
+            control1: Control1::from(control1),
+            charger_config3: ChargerConfig3::from(0),
+        }
+    }
+
+    fn events(previous: &Status, current: &Status) -> Vec<Event> {
+        Events::new(Interrupts::from_changes(previous, current), *current).collect()
+    }
+
+    #[test]
+    fn no_changes_no_flags() {
+        let status = status(0b1000_1110, FAST_CHARGE, 0b0110_0000, 1 << 4);
+        assert!(Interrupts::from_changes(&status, &status).is_empty());
+    }
+
+    #[test]
+    fn charger_status_changes() {
+        let previous = status(0, IDLE, 0, 0);
+        let current = status(0b1110_1110, IDLE, 0, 0);
+        assert_eq!(
+            events(&previous, &current),
+            vec![
+                Event::ThermalLoop { active: true },
+                Event::PowerSource { ok: true },
+                Event::ChargerActive { active: true },
+                Event::OverVoltage { active: true },
+                Event::TemperatureLow { active: true },
+                Event::TemperatureHigh { active: true },
+            ]
+        );
+        assert_eq!(
+            events(&current, &previous),
+            vec![
+                Event::ThermalLoop { active: false },
+                Event::PowerSource { ok: false },
+                Event::ChargerActive { active: false },
+                Event::OverVoltage { active: false },
+                Event::TemperatureLow { active: false },
+                Event::TemperatureHigh { active: false },
+            ]
+        );
+    }
+
+    #[test]
+    fn charger_state_change_is_a_single_event() {
+        let previous = status(0, IDLE, 0, 0);
+        let current = status(0, FAST_CHARGE, 0, 0);
+        let interrupts = Interrupts::from_changes(&previous, &current);
+        assert_eq!(u8::from(interrupts.ir1), IDLE | FAST_CHARGE);
+        assert_eq!(
+            events(&previous, &current),
+            vec![Event::ChargerState(ChargerState::from(FAST_CHARGE))]
+        );
+    }
+
+    #[test]
+    fn low_battery_push_button_and_rails() {
+        let previous = status(0, IDLE, 0b0110_0000, 0);
+        let mut current = status(0, IDLE, 0, 1 << 4);
+        current.charger_config3 = ChargerConfig3::from(1);
+        assert_eq!(
+            events(&previous, &current),
+            vec![
+                Event::LowBattery { low: true },
+                Event::PushButton { pressed: true },
+                Event::PowerGood {
+                    rail: Rail::Ldo1,
+                    good: true
+                },
+                Event::PowerGood {
+                    rail: Rail::Dcdc1,
+                    good: true
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn gpio_flags_are_never_set() {
+        let previous = status(0, IDLE, 0, 0);
+        let current = status(0xFF, 0xFF, 0xFF, 0xFF);
+        let interrupts = Interrupts::from_changes(&previous, &current);
+        assert_eq!(interrupts.bits() & 0xF0_0000, 0);
+    }
+
+    #[test]
+    fn masked_flags_give_no_events() {
+        let status = status(0b0000_1100, IDLE, 0, 1 << 4);
+        let interrupts = Interrupts::from_bits(0b1100 | 1 << 17);
+        let masks = InterruptMasks {
+            mask0: InterruptMask0::from(0b0100),
+            mask1: InterruptMask1::from(0),
+            mask2: InterruptMask2::from(1 << 1),
+        };
+        let events: Vec<_> = Events::unmasked(interrupts, status, Some(&masks)).collect();
+        assert_eq!(events, vec![Event::ChargerActive { active: true }]);
+        assert_eq!(Events::unmasked(interrupts, status, None).count(), 3);
+    }
+}
@@ -0,0 +1,252 @@
+use embedded_hal::i2c::{blocking::I2c, Error as I2cError, SevenBitAddress};
+
+use crate::{error::Error, registers::*, TPS6572x};
+
+/// Charge settings in a battery temperature band, in °C
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct JeitaBand {
+    pub min_c: i16,
+    pub max_c: i16,
+    pub charge_voltage: ChargeVoltage,
+    pub charge_current: ChargeCurrentFactor,
+}
+
+/// Usual JEITA bands, reduced current when cold and reduced voltage when warm
+pub const JEITA_BANDS: [JeitaBand; 3] = [
+    JeitaBand {
+        min_c: 0,
+        max_c: 10,
+        charge_voltage: ChargeVoltage::V4_200,
+        charge_current: ChargeCurrentFactor::P50,
+    },
+    JeitaBand {
+        min_c: 10,
+        max_c: 45,
+        charge_voltage: ChargeVoltage::V4_200,
+        charge_current: ChargeCurrentFactor::P100,
+    },
+    JeitaBand {
+        min_c: 45,
+        max_c: 60,
+        charge_voltage: ChargeVoltage::V4_150,
+        charge_current: ChargeCurrentFactor::P50,
+    },
+];
+
+/// Charge settings selected by the [`JeitaController`]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum JeitaAction {
+    Charge {
+        charge_voltage: ChargeVoltage,
+        charge_current: ChargeCurrentFactor,
+    },
+
+    /// Temperature outside of all the bands
+    Pause,
+}
+
+/// Software JEITA charge control from the battery temperature
+///
+/// Only selects the charge settings, the temperature being measured by the application,
+/// e.g. with the temperature output of the TPS657201 opamp mux. Between adjacent bands, a
+/// band is kept until the temperature is out of it by more than the hysteresis. Charging
+/// always pauses outside of the bands, and only resumes once the temperature is within
+/// the bands by the hysteresis.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct JeitaController<'a> {
+    bands: &'a [JeitaBand],
+    hysteresis_c: i16,
+    band: Option<usize>,
+
+    /// Whether the charger was disabled by [`TPS6572x::apply_jeita`]
+    paused_charger: bool,
+}
+
+impl<'a> JeitaController<'a> {
+    pub const fn new(bands: &'a [JeitaBand]) -> Self {
+        Self {
+            bands,
+            hysteresis_c: 2,
+            band: None,
+            paused_charger: false,
+        }
+    }
+
+    pub const fn with_hysteresis(mut self, hysteresis_c: i16) -> Self {
+        self.hysteresis_c = hysteresis_c;
+        self
+    }
+
+    /// Current band, `None` when paused
+    pub fn band(&self) -> Option<&JeitaBand> {
+        self.band.and_then(|i| self.bands.get(i))
+    }
+
+    /// Charge settings of the current band
+    pub fn action(&self) -> JeitaAction {
+        match self.band() {
+            Some(band) => JeitaAction::Charge {
+                charge_voltage: band.charge_voltage,
+                charge_current: band.charge_current,
+            },
+            None => JeitaAction::Pause,
+        }
+    }
+
+    /// Updates with the battery temperature, returning the charge settings
+    pub fn update(&mut self, temperature_c: i16) -> JeitaAction {
+        let h = self.hysteresis_c;
+        let position = |t: i16| {
+            self.bands
+                .iter()
+                .position(|b| (b.min_c..=b.max_c).contains(&t))
+        };
+
+        self.band = position(temperature_c).and_then(|band| {
+            let keep = self.band.filter(|&i| {
+                matches!(
+                    self.bands.get(i),
+                    Some(b) if (b.min_c.saturating_sub(h)..=b.max_c.saturating_add(h))
+                        .contains(&temperature_c)
+                )
+            });
+            let resume = self.band.is_some()
+                || (position(temperature_c.saturating_sub(h)).is_some()
+                    && position(temperature_c.saturating_add(h)).is_some());
+            keep.or_else(|| Some(band).filter(|_| resume))
+        });
+        self.action()
+    }
+}
+
+impl<I2C, I2CE> TPS6572x<I2C, I2CE>
+where
+    I2C: I2c<SevenBitAddress, Error = I2CE>,
+    I2CE: I2cError,
+{
+    /// Updates the controller with the battery temperature and applies its charge settings
+    pub fn update_jeita(
+        &mut self,
+        controller: &mut JeitaController,
+        temperature_c: i16,
+    ) -> Result<JeitaAction, Error<I2CE>> {
+        controller.update(temperature_c);
+        self.apply_jeita(controller)
+    }
+
+    /// Applies the charge settings of the controller, only writing the registers that change
+    ///
+    /// The charger is only enabled again if the controller was the one disabling it, so a
+    /// charger disabled by the application stays disabled.
+    pub fn apply_jeita(
+        &mut self,
+        controller: &mut JeitaController,
+    ) -> Result<JeitaAction, Error<I2CE>> {
+        let action = controller.action();
+        let config0 = self.read_register::<ChargerConfig0>()?;
+        let charge = match action {
+            JeitaAction::Pause => {
+                if config0.charger_enabled() {
+                    self.write_register(config0.with_charger_enabled(false))?;
+                    controller.paused_charger = true;
+                }
+                return Ok(action);
+            }
+            JeitaAction::Charge {
+                charge_voltage,
+                charge_current,
+            } => (charge_voltage, charge_current),
+        };
+
+        let config3 = self.read_register::<ChargerConfig3>()?;
+        if config3.charge_voltage() != charge.0 {
+            self.write_register(config3.with_charge_voltage(charge.0))?;
+        }
+
+        let config1 = self.read_register::<ChargerConfig1>()?;
+        if config1.charge_current_factor() != charge.1 {
+            self.write_register(config1.with_charge_current_factor(charge.1))?;
+        }
+
+        if controller.paused_charger && !config0.charger_enabled() {
+            self.write_register(config0.with_charger_enabled(true))?;
+        }
+        controller.paused_charger = false;
+        Ok(action)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COLD: JeitaAction = JeitaAction::Charge {
+        charge_voltage: ChargeVoltage::V4_200,
+        charge_current: ChargeCurrentFactor::P50,
+    };
+    const NORMAL: JeitaAction = JeitaAction::Charge {
+        charge_voltage: ChargeVoltage::V4_200,
+        charge_current: ChargeCurrentFactor::P100,
+    };
+    const WARM: JeitaAction = JeitaAction::Charge {
+        charge_voltage: ChargeVoltage::V4_150,
+        charge_current: ChargeCurrentFactor::P50,
+    };
+
+    #[test]
+    fn switches_bands_with_hysteresis() {
+        let mut jeita = JeitaController::new(&JEITA_BANDS);
+        assert_eq!(jeita.update(25), NORMAL);
+        assert_eq!(jeita.update(9), NORMAL);
+        assert_eq!(jeita.update(8), NORMAL);
+        assert_eq!(jeita.update(7), COLD);
+        assert_eq!(jeita.update(12), COLD);
+        assert_eq!(jeita.update(13), NORMAL);
+        assert_eq!(jeita.update(47), NORMAL);
+        assert_eq!(jeita.update(48), WARM);
+        assert_eq!(jeita.update(43), WARM);
+        assert_eq!(jeita.update(42), NORMAL);
+    }
+
+    #[test]
+    fn pauses_at_the_outer_limits() {
+        let mut jeita = JeitaController::new(&JEITA_BANDS);
+        assert_eq!(jeita.update(55), WARM);
+        assert_eq!(jeita.update(60), WARM);
+        assert_eq!(jeita.update(61), JeitaAction::Pause);
+
+        assert_eq!(jeita.update(62), JeitaAction::Pause);
+        assert_eq!(jeita.update(5), COLD);
+        assert_eq!(jeita.update(0), COLD);
+        assert_eq!(jeita.update(-1), JeitaAction::Pause);
+        assert_eq!(jeita.update(i16::MIN), JeitaAction::Pause);
+        assert_eq!(jeita.update(i16::MAX), JeitaAction::Pause);
+    }
+
+    #[test]
+    fn resumes_within_the_limits_by_the_hysteresis() {
+        let mut jeita = JeitaController::new(&JEITA_BANDS);
+        assert_eq!(jeita.update(62), JeitaAction::Pause);
+        assert_eq!(jeita.update(60), JeitaAction::Pause);
+        assert_eq!(jeita.update(59), JeitaAction::Pause);
+        assert_eq!(jeita.update(58), WARM);
+
+        assert_eq!(jeita.update(-1), JeitaAction::Pause);
+        assert_eq!(jeita.update(1), JeitaAction::Pause);
+        assert_eq!(jeita.update(2), COLD);
+    }
+
+    #[test]
+    fn starts_paused_near_the_limits() {
+        let mut jeita = JeitaController::new(&JEITA_BANDS);
+        assert_eq!(jeita.update(1), JeitaAction::Pause);
+        assert_eq!(jeita.band(), None);
+
+        let mut jeita = JeitaController::new(&JEITA_BANDS).with_hysteresis(0);
+        assert_eq!(jeita.update(0), COLD);
+        assert_eq!(jeita.update(60), WARM);
+    }
+}
@@ -1,28 +1,124 @@
 #![no_std]
 
+#[cfg(feature = "async")]
+pub mod asynch;
+
+pub mod battery;
+pub mod charger;
+pub mod config;
+pub mod dcdc_mode;
+pub mod dvs;
 pub mod error;
+pub mod interrupt;
+pub mod jeita;
+#[cfg(test)]
+mod mock;
+pub mod monitor;
+pub mod opamp;
+pub mod power_good;
+pub mod power_latch;
+pub mod power_off;
+pub mod power_path;
+pub mod probe;
+pub mod push_button;
+pub mod rail;
+pub mod ramp;
 pub mod registers;
+pub mod reset;
+pub mod sequence;
+pub mod session;
+pub mod shared;
+pub mod soc;
+pub mod thermal;
+pub mod timers;
+pub mod usb;
 
-use embedded_hal::i2c::{blocking::I2c, SevenBitAddress};
+use core::time::Duration;
+use embedded_hal::{
+    delay::blocking::DelayUs,
+    i2c::{blocking::I2c, Error as I2cError, SevenBitAddress},
+};
 use error::Error;
 use registers::*;
 
-const TPS_ADDRESS: u8 = 0b100_1000;
+/// Default i2c address of the chip
+pub const TPS_ADDRESS: u8 = 0b100_1000;
+
+/// Variant of the TPS6572x family
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Variant {
+    /// Variants without the opamp mux
+    TPS65720,
+
+    /// Variant with the battery voltage and temperature opamp mux
+    TPS657201,
+}
+
+impl Variant {
+    /// Variant selected at compile time with the `tps_model` cfg
+    pub const fn compiled() -> Self {
+        #[cfg(tps_model = "TPS657201")]
+        return Variant::TPS657201;
 
+        #[cfg(not(tps_model = "TPS657201"))]
+        return Variant::TPS65720;
+    }
+}
+
+/// Driver for the TPS6572x
+///
+/// Multiple chips can share a bus, each driver using its own handle on the bus,
+/// e.g. the proxies of a shared-bus manager, and its own address.
+///
+/// ```
+/// use embedded_hal::i2c::{blocking::I2c, Error, SevenBitAddress};
+/// use tps6572x::{registers::ChargerStatus, TPS6572x};
+///
+/// fn any_charging<P, E>(main_bus: P, backup_bus: P) -> bool
+/// where
+///     P: I2c<SevenBitAddress, Error = E>,
+///     E: Error,
+/// {
+///     let mut main = TPS6572x::new(main_bus);
+///     let mut backup = TPS6572x::new(backup_bus).with_address(0x49);
+///
+///     [main.read_register::<ChargerStatus>(), backup.read_register()]
+///         .iter()
+///         .any(|status| matches!(status, Ok(s) if s.charger_active()))
+/// }
+/// ```
 pub struct TPS6572x<I2C, I2CE>
 where
     I2C: I2c<SevenBitAddress, Error = I2CE>,
 {
     i2c: I2C,
+    address: SevenBitAddress,
 }
 
 impl<I2C, I2CE> TPS6572x<I2C, I2CE>
 where
     I2C: I2c<SevenBitAddress, Error = I2CE>,
+    I2CE: I2cError,
 {
     pub fn new(i2c: I2C) -> Self {
-        Self { i2c }
+        Self {
+            i2c,
+            address: TPS_ADDRESS,
+        }
+    }
+
+    /// Uses another address than [`TPS_ADDRESS`], e.g. behind an address translator
+    pub fn with_address(mut self, address: SevenBitAddress) -> Self {
+        self.address = address;
+        self
     }
+
+    /// Address of the chip on the bus
+    pub fn address(&self) -> SevenBitAddress {
+        self.address
+    }
+
     pub fn release(self) -> I2C {
         self.i2c
     }
@@ -33,8 +129,8 @@ where
         u8: From<R>,
     {
         self.i2c
-            .write(TPS_ADDRESS, &[R::ADDRESS as u8, register.into()])
-            .map_err(Error::I2c)?;
+            .write(self.address, &[R::ADDRESS as u8, register.into()])
+            .map_err(Error::from_i2c)?;
         Ok(())
     }
 
@@ -42,12 +138,18 @@ where
     where
         R: Register + From<u8>,
     {
+        Ok(self.read_raw(R::ADDRESS)?.into())
+    }
+
+    fn read_raw(&mut self, address: RegisterAddress) -> Result<u8, Error<I2CE>> {
         let mut val: [u8; 1] = [0; 1];
         self.i2c
-            .write(TPS_ADDRESS, &[R::ADDRESS as u8])
-            .map_err(Error::I2c)?;
-        self.i2c.read(TPS_ADDRESS, &mut val).map_err(Error::I2c)?;
-        Ok(val[0].into())
+            .write(self.address, &[address as u8])
+            .map_err(Error::from_i2c)?;
+        self.i2c
+            .read(self.address, &mut val)
+            .map_err(Error::from_i2c)?;
+        Ok(val[0])
     }
 
     pub fn edit_register<R, F>(&mut self, f: F) -> Result<(), Error<I2CE>>
@@ -61,3 +163,12 @@ where
         self.write_register(new_val)
     }
 }
+
+/// Waits for the duration, saturating at the longest delay supported by `DelayUs`
+pub(crate) fn delay_for<D, I2CE>(delay: &mut D, duration: Duration) -> Result<(), Error<I2CE>>
+where
+    D: DelayUs,
+{
+    let us = u32::try_from(duration.as_micros()).unwrap_or(u32::MAX);
+    delay.delay_us(us).map_err(|_| Error::Delay)
+}
@@ -0,0 +1,95 @@
+//! Register file standing in for the chip in the host tests
+
+extern crate std;
+
+use embedded_hal::i2c::{
+    blocking::{I2c, Operation},
+    Error as I2cError, ErrorKind, ErrorType,
+};
+use std::vec::Vec;
+
+#[derive(Debug, PartialEq)]
+pub struct BusError(pub ErrorKind);
+
+impl I2cError for BusError {
+    fn kind(&self) -> ErrorKind {
+        self.0
+    }
+}
+
+/// Registers of the chip, recording the writes
+#[derive(Default)]
+pub struct Chip {
+    pub registers: [u8; 0x13],
+    pub writes: Vec<(u8, u8)>,
+
+    /// Error of every transfer, e.g. when no chip answers
+    pub error: Option<ErrorKind>,
+
+    pointer: usize,
+}
+
+impl Chip {
+    /// Chip failing every transfer with the error
+    pub fn failing(kind: ErrorKind) -> Self {
+        Self {
+            error: Some(kind),
+            ..Self::default()
+        }
+    }
+}
+
+impl ErrorType for Chip {
+    type Error = BusError;
+}
+
+impl I2c for Chip {
+    fn read(&mut self, _: u8, buffer: &mut [u8]) -> Result<(), BusError> {
+        if let Some(kind) = self.error {
+            return Err(BusError(kind));
+        }
+        buffer.fill(self.registers[self.pointer]);
+        Ok(())
+    }
+
+    fn write(&mut self, _: u8, bytes: &[u8]) -> Result<(), BusError> {
+        if let Some(kind) = self.error {
+            return Err(BusError(kind));
+        }
+        self.pointer = bytes[0].into();
+        if let Some(&value) = bytes.get(1) {
+            self.registers[self.pointer] = value;
+            self.writes.push((bytes[0], value));
+        }
+        Ok(())
+    }
+
+    fn write_iter<B>(&mut self, _: u8, _: B) -> Result<(), BusError>
+    where
+        B: IntoIterator<Item = u8>,
+    {
+        unimplemented!()
+    }
+
+    fn write_read(&mut self, _: u8, _: &[u8], _: &mut [u8]) -> Result<(), BusError> {
+        unimplemented!()
+    }
+
+    fn write_iter_read<B>(&mut self, _: u8, _: B, _: &mut [u8]) -> Result<(), BusError>
+    where
+        B: IntoIterator<Item = u8>,
+    {
+        unimplemented!()
+    }
+
+    fn transaction<'a>(&mut self, _: u8, _: &mut [Operation<'a>]) -> Result<(), BusError> {
+        unimplemented!()
+    }
+
+    fn transaction_iter<'a, O>(&mut self, _: u8, _: O) -> Result<(), BusError>
+    where
+        O: IntoIterator<Item = Operation<'a>>,
+    {
+        unimplemented!()
+    }
+}
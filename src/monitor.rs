@@ -0,0 +1,77 @@
+use embedded_hal::i2c::{blocking::I2c, Error as I2cError, SevenBitAddress};
+
+use crate::{
+    error::Error,
+    interrupt::{Events, Interrupts, Status},
+    TPS6572x,
+};
+
+/// Events from polling the status registers, for boards without the INT pin wired
+///
+/// Each sample is compared with the previous one and the changes are reported as the
+/// [`Events`] of the interrupt path. Changes shorter than the polling period, e.g. a
+/// quick push button press, can be missed, and the GPIO inputs are not monitored.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub struct Monitor {
+    previous: Option<Status>,
+}
+
+impl Monitor {
+    pub const fn new() -> Self {
+        Self { previous: None }
+    }
+
+    /// Last sample, `None` before the first one
+    pub fn status(&self) -> Option<&Status> {
+        self.previous.as_ref()
+    }
+
+    /// Updates with a new sample, returning the events of the changes
+    ///
+    /// The first sample only sets the reference and gives no event.
+    pub fn update(&mut self, status: Status) -> Events {
+        let previous = self.previous.replace(status).unwrap_or(status);
+        Events::new(Interrupts::from_changes(&previous, &status), status)
+    }
+}
+
+impl<I2C, I2CE> TPS6572x<I2C, I2CE>
+where
+    I2C: I2c<SevenBitAddress, Error = I2CE>,
+    I2CE: I2cError,
+{
+    /// Reads the status registers, returning the events since the previous poll
+    pub fn poll_events(&mut self, monitor: &mut Monitor) -> Result<Events, Error<I2CE>> {
+        let status = self.read_status()?;
+        Ok(monitor.update(status))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{interrupt::Event, registers::*};
+
+    fn status(charger_status: u8) -> Status {
+        Status {
+            charger_status: ChargerStatus::from(charger_status),
+            charger_state: ChargerState::from(1 << 5),
+            control0: Control0::from(0),
+            control1: Control1::from(0),
+            charger_config3: ChargerConfig3::from(0),
+        }
+    }
+
+    #[test]
+    fn reports_the_changes_since_the_previous_sample() {
+        let mut monitor = Monitor::new();
+        assert_eq!(monitor.update(status(0b100)).next(), None);
+        assert_eq!(monitor.status(), Some(&status(0b100)));
+        assert_eq!(monitor.update(status(0b100)).next(), None);
+
+        let mut events = monitor.update(status(0));
+        assert_eq!(events.next(), Some(Event::PowerSource { ok: false }));
+        assert_eq!(events.next(), None);
+    }
+}
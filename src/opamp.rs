@@ -0,0 +1,46 @@
+use embedded_hal::i2c::{blocking::I2c, Error as I2cError, SevenBitAddress};
+
+use crate::{error::Error, registers::OpampMuxMeasurement, TPS6572x};
+
+impl<I2C, I2CE> TPS6572x<I2C, I2CE>
+where
+    I2C: I2c<SevenBitAddress, Error = I2CE>,
+    I2CE: I2cError,
+{
+    /// Enables the opamp mux and routes the measurement to its output, for an external ADC
+    #[cfg(tps_model = "TPS657201")]
+    pub fn select_opamp_measurement(
+        &mut self,
+        measurement: OpampMuxMeasurement,
+    ) -> Result<(), Error<I2CE>> {
+        use crate::registers::Control1;
+
+        self.edit_register(|r: Control1| {
+            r.with_opamp_mux_enabled(true)
+                .with_opamp_mux_measurement(measurement)
+        })
+    }
+
+    /// The opamp mux is only available on the TPS657201
+    #[cfg(not(tps_model = "TPS657201"))]
+    pub fn select_opamp_measurement(
+        &mut self,
+        _measurement: OpampMuxMeasurement,
+    ) -> Result<(), Error<I2CE>> {
+        Err(Error::UnsupportedOnVariant)
+    }
+
+    /// Disables the opamp mux
+    #[cfg(tps_model = "TPS657201")]
+    pub fn disable_opamp(&mut self) -> Result<(), Error<I2CE>> {
+        use crate::registers::Control1;
+
+        self.edit_register(|r: Control1| r.with_opamp_mux_enabled(false))
+    }
+
+    /// The opamp mux is only available on the TPS657201
+    #[cfg(not(tps_model = "TPS657201"))]
+    pub fn disable_opamp(&mut self) -> Result<(), Error<I2CE>> {
+        Err(Error::UnsupportedOnVariant)
+    }
+}
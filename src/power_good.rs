@@ -0,0 +1,213 @@
+use embedded_hal::i2c::{blocking::I2c, Error as I2cError, SevenBitAddress};
+
+use crate::{error::Error, rail::Rail, registers::Control0, TPS6572x};
+
+/// Regulation state of a rail
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum RailState {
+    /// Output in regulation
+    PowerGood,
+
+    /// Output out of regulation, or rail disabled
+    OutOfRegulation,
+}
+
+impl From<bool> for RailState {
+    fn from(power_good: bool) -> Self {
+        if power_good {
+            RailState::PowerGood
+        } else {
+            RailState::OutOfRegulation
+        }
+    }
+}
+
+/// Change of the debounced state of a rail
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct RailEvent {
+    pub rail: Rail,
+    pub state: RailState,
+}
+
+/// Debounced state of a single rail
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, PartialEq, Debug)]
+struct Debounce {
+    state: Option<RailState>,
+    candidate: RailState,
+    count: u8,
+}
+
+impl Debounce {
+    const fn new() -> Self {
+        Self {
+            state: None,
+            candidate: RailState::OutOfRegulation,
+            count: 0,
+        }
+    }
+
+    /// Returns the new state if it changed from a previously debounced state
+    fn update(&mut self, sample: RailState, samples: u8) -> Option<RailState> {
+        if sample == self.candidate {
+            self.count = self.count.saturating_add(1);
+        } else {
+            self.candidate = sample;
+            self.count = 1;
+        }
+
+        if self.count >= samples && self.state != Some(sample) {
+            return self.state.replace(sample).map(|_| sample);
+        }
+        None
+    }
+}
+
+/// Debounced health of the DCDC1 and LDO1 rails
+///
+/// Fed with the PGOODZ bits of [`Control0`], either periodically or when the PGOODZ
+/// bits of `Interrupt2` are set. A state is only reported once it was read for
+/// a number of consecutive samples, and a [`RailEvent`] is emitted when the debounced
+/// state of a rail changes, e.g. when it drops out of regulation.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct RailHealth {
+    samples: u8,
+    dcdc1: Debounce,
+    ldo1: Debounce,
+}
+
+impl RailHealth {
+    /// Debounces the rails over `samples` consecutive samples, at least one
+    pub const fn new(samples: u8) -> Self {
+        Self {
+            samples: if samples == 0 { 1 } else { samples },
+            dcdc1: Debounce::new(),
+            ldo1: Debounce::new(),
+        }
+    }
+
+    /// Debounced state of the rail, `None` until enough samples were read
+    pub fn state(&self, rail: Rail) -> Option<RailState> {
+        match rail {
+            Rail::Dcdc1 => self.dcdc1.state,
+            Rail::Ldo1 => self.ldo1.state,
+        }
+    }
+
+    /// Updates the rails with a new sample, returning the state changes
+    pub fn update(&mut self, control0: Control0) -> impl Iterator<Item = RailEvent> {
+        let samples = self.samples;
+        let dcdc1 = self
+            .dcdc1
+            .update(Rail::Dcdc1.power_good(control0).into(), samples)
+            .map(|state| RailEvent {
+                rail: Rail::Dcdc1,
+                state,
+            });
+        let ldo1 = self
+            .ldo1
+            .update(Rail::Ldo1.power_good(control0).into(), samples)
+            .map(|state| RailEvent {
+                rail: Rail::Ldo1,
+                state,
+            });
+
+        [dcdc1, ldo1].into_iter().flatten()
+    }
+}
+
+impl<I2C, I2CE> TPS6572x<I2C, I2CE>
+where
+    I2C: I2c<SevenBitAddress, Error = I2CE>,
+    I2CE: I2cError,
+{
+    /// Samples the power good bits into the rail health, returning the state changes
+    pub fn poll_rail_health(
+        &mut self,
+        health: &mut RailHealth,
+    ) -> Result<impl Iterator<Item = RailEvent>, Error<I2CE>> {
+        Ok(health.update(self.read_register()?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::{vec, vec::Vec};
+
+    use super::*;
+
+    /// Control0 with the PGOODZ bits of the rails out of regulation set
+    fn control0(dcdc1_good: bool, ldo1_good: bool) -> Control0 {
+        Control0::from(u8::from(!ldo1_good) << 5 | u8::from(!dcdc1_good) << 6)
+    }
+
+    fn update(health: &mut RailHealth, dcdc1_good: bool, ldo1_good: bool) -> Vec<RailEvent> {
+        health.update(control0(dcdc1_good, ldo1_good)).collect()
+    }
+
+    fn event(rail: Rail, state: RailState) -> RailEvent {
+        RailEvent { rail, state }
+    }
+
+    #[test]
+    fn settles_without_events() {
+        let mut health = RailHealth::new(3);
+        assert_eq!(update(&mut health, true, false), vec![]);
+        assert_eq!(update(&mut health, true, false), vec![]);
+        assert_eq!(health.state(Rail::Dcdc1), None);
+        assert_eq!(update(&mut health, true, false), vec![]);
+        assert_eq!(health.state(Rail::Dcdc1), Some(RailState::PowerGood));
+        assert_eq!(health.state(Rail::Ldo1), Some(RailState::OutOfRegulation));
+    }
+
+    #[test]
+    fn reports_changes_after_consecutive_samples() {
+        let mut health = RailHealth::new(2);
+        update(&mut health, true, true);
+        update(&mut health, true, true);
+
+        assert_eq!(update(&mut health, false, true), vec![]);
+        assert_eq!(
+            update(&mut health, false, true),
+            vec![event(Rail::Dcdc1, RailState::OutOfRegulation)]
+        );
+        assert_eq!(update(&mut health, false, true), vec![]);
+
+        assert_eq!(update(&mut health, true, false), vec![]);
+        assert_eq!(
+            update(&mut health, true, false),
+            vec![
+                event(Rail::Dcdc1, RailState::PowerGood),
+                event(Rail::Ldo1, RailState::OutOfRegulation),
+            ]
+        );
+    }
+
+    #[test]
+    fn ignores_glitches() {
+        let mut health = RailHealth::new(3);
+        for _ in 0..3 {
+            update(&mut health, true, true);
+        }
+        for _ in 0..4 {
+            assert_eq!(update(&mut health, false, true), vec![]);
+            assert_eq!(update(&mut health, true, true), vec![]);
+        }
+        assert_eq!(health.state(Rail::Dcdc1), Some(RailState::PowerGood));
+    }
+
+    #[test]
+    fn zero_samples_is_one() {
+        let mut health = RailHealth::new(0);
+        update(&mut health, true, true);
+        assert_eq!(
+            update(&mut health, true, false),
+            vec![event(Rail::Ldo1, RailState::OutOfRegulation)]
+        );
+    }
+}
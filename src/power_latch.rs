@@ -0,0 +1,56 @@
+use embedded_hal::i2c::{blocking::I2c, Error as I2cError, SevenBitAddress};
+
+use crate::{error::Error, rail::Rail, registers::Control1, TPS6572x};
+
+/// Soft power latch using the HOLD bits of DCDC1 and LDO1
+///
+/// At power up the rails are enabled by the power button or enable input. While
+/// HOLD_DCDC1 and HOLD_LDO1 are set the rails stay enabled once the input is released,
+/// clearing them turns the rails, and the system they supply, off.
+pub struct PowerLatch<'a, I2C, I2CE>
+where
+    I2C: I2c<SevenBitAddress, Error = I2CE>,
+{
+    tps: &'a mut TPS6572x<I2C, I2CE>,
+}
+
+impl<I2C, I2CE> TPS6572x<I2C, I2CE>
+where
+    I2C: I2c<SevenBitAddress, Error = I2CE>,
+    I2CE: I2cError,
+{
+    pub fn power_latch(&mut self) -> PowerLatch<'_, I2C, I2CE> {
+        PowerLatch { tps: self }
+    }
+}
+
+impl<'a, I2C, I2CE> PowerLatch<'a, I2C, I2CE>
+where
+    I2C: I2c<SevenBitAddress, Error = I2CE>,
+    I2CE: I2cError,
+{
+    /// Sets the HOLD bits, keeping the rails on once the enable input is released
+    pub fn latch_on(&mut self) -> Result<(), Error<I2CE>> {
+        self.tps.set_rail_hold(Rail::Dcdc1, true)?;
+        self.tps.set_rail_hold(Rail::Ldo1, true)
+    }
+
+    /// Whether both HOLD bits are set
+    pub fn is_latched(&mut self) -> Result<bool, Error<I2CE>> {
+        Ok(self.tps.rail_hold(Rail::Dcdc1)? && self.tps.rail_hold(Rail::Ldo1)?)
+    }
+
+    /// Whether the rails are enabled, by the HOLD bits or the enable input
+    pub fn rails_enabled(&mut self) -> Result<bool, Error<I2CE>> {
+        Ok(self.tps.read_register::<Control1>()?.dcdc_ldo_enabled())
+    }
+
+    /// Clears the HOLD bits, LDO1 first and DCDC1 last
+    ///
+    /// If DCDC1 supplies the MCU, this does not return unless the rails are kept on by
+    /// the enable input, e.g. the power button is still pressed.
+    pub fn release_and_power_off(&mut self) -> Result<(), Error<I2CE>> {
+        self.tps.set_rail_hold(Rail::Ldo1, false)?;
+        self.tps.set_rail_hold(Rail::Dcdc1, false)
+    }
+}
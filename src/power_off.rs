@@ -0,0 +1,107 @@
+use core::time::Duration;
+use embedded_hal::i2c::{blocking::I2c, Error as I2cError, SevenBitAddress};
+
+use crate::{
+    error::Error,
+    push_button::{ButtonEvent, PushButton, PushButtonConfig},
+    registers::ChargerConfig0,
+    TPS6572x,
+};
+
+/// Application hooks of the [`PowerOffPolicy`]
+pub trait PowerOffHooks {
+    /// Whether the power off must be postponed, e.g. while writing to flash
+    fn veto(&mut self) -> bool {
+        false
+    }
+
+    /// Called right before the rails are turned off
+    fn shutdown(&mut self);
+}
+
+/// Soft power off on a long press of the push button
+///
+/// On a long press, the shutdown hook is called, the charger is disabled if configured,
+/// then the HOLD bits are cleared with [`PowerLatch::release_and_power_off`](crate::power_latch::PowerLatch::release_and_power_off).
+/// A vetoed power off stays pending and is retried on the next updates.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct PowerOffPolicy {
+    button: PushButton,
+    disable_charger: bool,
+    pending: bool,
+}
+
+impl PowerOffPolicy {
+    /// Powers off when the button is held for `long_press`
+    pub const fn new(long_press: Duration) -> Self {
+        Self {
+            button: PushButton::new(PushButtonConfig {
+                long_press,
+                double_click: Duration::ZERO,
+            }),
+            disable_charger: false,
+            pending: false,
+        }
+    }
+
+    /// Disables the charger before powering off, so the system does not stay up while charging
+    pub const fn with_charger_disabled(mut self, disable_charger: bool) -> Self {
+        self.disable_charger = disable_charger;
+        self
+    }
+
+    /// Whether a power off was requested but vetoed
+    pub fn is_pending(&self) -> bool {
+        self.pending
+    }
+
+    /// Updates with the state of the push button, powering off after a long press
+    ///
+    /// Should be called on the push button events and periodically. If the power off
+    /// succeeds and DCDC1 supplies the MCU, this does not return.
+    pub fn update<I2C, I2CE, H>(
+        &mut self,
+        tps: &mut TPS6572x<I2C, I2CE>,
+        pressed: bool,
+        now: Duration,
+        hooks: &mut H,
+    ) -> Result<(), Error<I2CE>>
+    where
+        I2C: I2c<SevenBitAddress, Error = I2CE>,
+        I2CE: I2cError,
+        H: PowerOffHooks,
+    {
+        if self
+            .button
+            .update(pressed, now)
+            .any(|e| matches!(e, ButtonEvent::LongPress(_)))
+        {
+            self.pending = true;
+        }
+
+        if self.pending && !hooks.veto() {
+            self.pending = false;
+            self.power_off(tps, hooks)?;
+        }
+        Ok(())
+    }
+
+    /// Runs the power off immediately, without checking the veto
+    pub fn power_off<I2C, I2CE, H>(
+        &self,
+        tps: &mut TPS6572x<I2C, I2CE>,
+        hooks: &mut H,
+    ) -> Result<(), Error<I2CE>>
+    where
+        I2C: I2c<SevenBitAddress, Error = I2CE>,
+        I2CE: I2cError,
+        H: PowerOffHooks,
+    {
+        hooks.shutdown();
+        if self.disable_charger {
+            tps.edit_register(|r: ChargerConfig0| r.with_charger_enabled(false))?;
+        }
+        tps.power_latch().release_and_power_off()
+    }
+}
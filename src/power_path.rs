@@ -0,0 +1,94 @@
+use embedded_hal::i2c::{blocking::I2c, Error as I2cError, SevenBitAddress};
+
+use crate::{error::Error, registers::*, TPS6572x};
+
+/// System output and dynamic power path management (DPPM)
+///
+/// The SYS rail is supplied from the input and the battery. A higher SYS voltage gives
+/// more headroom to the loads but more losses, and must stay above the charge voltage
+/// for the battery to fully charge. When the input can't supply both the system and the
+/// charger, DPPM reduces the charge current to keep SYS above its threshold: the
+/// `VBAT + 100 mV` threshold maximizes the charge current, while the 4.3 V threshold keeps
+/// more margin for the system loads at the cost of a slower charge.
+pub struct PowerPath<'a, I2C, I2CE>
+where
+    I2C: I2c<SevenBitAddress, Error = I2CE>,
+{
+    tps: &'a mut TPS6572x<I2C, I2CE>,
+}
+
+impl<I2C, I2CE> TPS6572x<I2C, I2CE>
+where
+    I2C: I2c<SevenBitAddress, Error = I2CE>,
+    I2CE: I2cError,
+{
+    pub fn power_path(&mut self) -> PowerPath<'_, I2C, I2CE> {
+        PowerPath { tps: self }
+    }
+}
+
+impl<'a, I2C, I2CE> PowerPath<'a, I2C, I2CE>
+where
+    I2C: I2c<SevenBitAddress, Error = I2CE>,
+    I2CE: I2cError,
+{
+    /// Output voltage of the SYS rail
+    pub fn sys_voltage(&mut self) -> Result<SysOutputVoltage, Error<I2CE>> {
+        Ok(self.tps.read_register::<ChargerConfig0>()?.output_voltage())
+    }
+
+    /// Sets the output voltage of the SYS rail
+    ///
+    /// Every regulated SYS voltage is above the highest charge voltage, and in the `Vsys`
+    /// mode SYS follows the battery through the power path, so SYS never limits the charge.
+    pub fn set_sys_voltage(&mut self, voltage: SysOutputVoltage) -> Result<(), Error<I2CE>> {
+        self.tps
+            .edit_register(|r: ChargerConfig0| r.with_output_voltage(voltage))
+    }
+
+    /// SYS voltage below which DPPM reduces the charge current
+    pub fn dppm_threshold(&mut self) -> Result<PowerBatThreshold, Error<I2CE>> {
+        Ok(self
+            .tps
+            .read_register::<ChargerConfig2>()?
+            .dynamic_power_path_treshold())
+    }
+
+    /// Sets the SYS voltage below which DPPM reduces the charge current
+    pub fn set_dppm_threshold(&mut self, threshold: PowerBatThreshold) -> Result<(), Error<I2CE>> {
+        self.tps
+            .edit_register(|r: ChargerConfig2| r.with_dynamic_power_path_treshold(threshold))
+    }
+
+    /// Whether DPPM is likely reducing the charge current
+    ///
+    /// The chip has no DPPM status, it is inferred from the charger being in LDO mode
+    /// while the input is good and the charger is active, outside of thermal regulation.
+    pub fn is_dppm_active(&mut self) -> Result<bool, Error<I2CE>> {
+        let status = self.tps.read_register::<ChargerStatus>()?;
+        let state = self.tps.read_register::<ChargerState>()?;
+        Ok(status.power_source_ok()
+            && status.charger_active()
+            && !status.thermal_loop_active()
+            && state.ldo())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sys_voltages_are_above_the_charge_voltages() {
+        let sys = [
+            SysOutputVoltage::V4_4,
+            SysOutputVoltage::V5,
+            SysOutputVoltage::V5_5,
+        ];
+        let highest_charge = ChargeVoltage::V4_325.millivolts();
+        for voltage in sys {
+            assert!(voltage.millivolts().unwrap() > highest_charge);
+        }
+        assert_eq!(SysOutputVoltage::Vsys.millivolts(), None);
+    }
+}
@@ -0,0 +1,115 @@
+use embedded_hal::i2c::{blocking::I2c, Error as I2cError, SevenBitAddress};
+
+use crate::{error::Error, registers::RegisterAddress, TPS6572x, Variant};
+
+/// Bits that are not used and always read as zero, per register
+const RESERVED_BITS: [(RegisterAddress, u8); 5] = [
+    (RegisterAddress::ChargerStatus, 0b0001_0001),
+    (RegisterAddress::ChargerConfig1, 0b0000_0011),
+    (RegisterAddress::Control0, 0b0001_1111),
+    (RegisterAddress::Control1, 0b1100_0000),
+    (RegisterAddress::GPIOSSC, 0b1111_0000),
+];
+
+/// OPAMP_EN and OPAMP_MUX of CONTROL1, only present on the TPS657201
+const OPAMP_BITS: u8 = 0b0000_0110;
+
+impl<I2C, I2CE> TPS6572x<I2C, I2CE>
+where
+    I2C: I2c<SevenBitAddress, Error = I2CE>,
+    I2CE: I2cError,
+{
+    /// Creates the driver only if a TPS6572x answers on the bus
+    ///
+    /// Fails with [`Error::DeviceNotFound`] if the address is not acknowledged or if another
+    /// device answers at the address, and with [`Error::UnsupportedOnVariant`]
+    /// if the chip is not the variant the crate was compiled for. Other bus errors are
+    /// returned as [`Error::I2c`].
+    pub fn probe(i2c: I2C) -> Result<Self, Error<I2CE>> {
+        let mut tps = Self::new(i2c);
+        tps.check_presence()?;
+        match tps.guess_variant()? {
+            Some(variant) if variant != Variant::compiled() => Err(Error::UnsupportedOnVariant),
+            _ => Ok(tps),
+        }
+    }
+
+    /// Checks that a TPS6572x answers and that the unused bits of its registers read as zero
+    ///
+    /// Only a NACK of the address means no chip, see [`Error::from_i2c`].
+    pub fn check_presence(&mut self) -> Result<(), Error<I2CE>> {
+        for (address, reserved) in RESERVED_BITS {
+            let val = self.read_raw(address)?;
+
+            if val & reserved != 0 {
+                return Err(Error::DeviceNotFound(None));
+            }
+        }
+        Ok(())
+    }
+
+    /// Guesses the variant of the chip from the opamp bits of CONTROL1
+    ///
+    /// Returns `None` when the variant cannot be told apart, e.g. when the opamp
+    /// of a TPS657201 is disabled.
+    pub fn guess_variant(&mut self) -> Result<Option<Variant>, Error<I2CE>> {
+        let control1 = self.read_raw(RegisterAddress::Control1)?;
+        if control1 & OPAMP_BITS != 0 {
+            Ok(Some(Variant::TPS657201))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use embedded_hal::i2c::{ErrorKind, NoAcknowledgeSource};
+
+    use super::*;
+    use crate::mock::{BusError, Chip};
+
+    fn check_presence(chip: &mut Chip) -> Result<(), Error<BusError>> {
+        TPS6572x::new(chip).check_presence()
+    }
+
+    #[test]
+    fn finds_the_chip() {
+        assert!(check_presence(&mut Chip::default()).is_ok());
+    }
+
+    #[test]
+    fn address_nack_is_no_device() {
+        let kind = ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address);
+        let mut chip = Chip::failing(kind);
+        assert!(matches!(
+            check_presence(&mut chip),
+            Err(Error::DeviceNotFound(Some(BusError(k)))) if k == kind
+        ));
+    }
+
+    #[test]
+    fn other_bus_errors_are_passed_through() {
+        for kind in [
+            ErrorKind::Bus,
+            ErrorKind::ArbitrationLoss,
+            ErrorKind::NoAcknowledge(NoAcknowledgeSource::Data),
+        ] {
+            let mut chip = Chip::failing(kind);
+            assert!(matches!(
+                check_presence(&mut chip),
+                Err(Error::I2c(BusError(k))) if k == kind
+            ));
+        }
+    }
+
+    #[test]
+    fn reserved_bits_set_is_another_device() {
+        let mut chip = Chip::default();
+        chip.registers[RegisterAddress::GPIOSSC as usize] = 0xFF;
+        assert!(matches!(
+            check_presence(&mut chip),
+            Err(Error::DeviceNotFound(None))
+        ));
+    }
+}
@@ -0,0 +1,286 @@
+use core::time::Duration;
+use embedded_hal::i2c::{blocking::I2c, Error as I2cError, SevenBitAddress};
+
+use crate::{error::Error, interrupt::Event, registers::Control1, TPS6572x};
+
+/// Gesture of the push button
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum ButtonEvent {
+    Pressed,
+    Released,
+
+    /// Released before the long press threshold, without a second click, also emitted
+    /// when the press following the click is held into a long press
+    ShortPress,
+
+    /// Held for the long press threshold, with the time it was held for
+    LongPress(Duration),
+
+    /// Released a second time, the second press being within the double click window
+    DoubleClick,
+}
+
+/// Thresholds of the push button gestures
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct PushButtonConfig {
+    /// Time held before a long press
+    pub long_press: Duration,
+
+    /// Longest time between a release and the second press of a double click,
+    /// zero disables double clicks
+    pub double_click: Duration,
+}
+
+impl Default for PushButtonConfig {
+    fn default() -> Self {
+        Self {
+            long_press: Duration::from_secs(1),
+            double_click: Duration::from_millis(300),
+        }
+    }
+}
+
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, PartialEq, Debug)]
+enum State {
+    Idle,
+    Pressed { since: Duration, second: bool },
+    LongPressed,
+    WaitSecond { released_at: Duration },
+}
+
+/// Push button state machine
+///
+/// Fed with the button state and a monotonic timestamp, from the `PushButton`
+/// interrupt events or by polling PB_STAT. The time based gestures are only
+/// emitted on updates, so it should also be updated periodically with [`PushButton::poll`].
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct PushButton {
+    config: PushButtonConfig,
+    state: State,
+    pressed: bool,
+}
+
+impl PushButton {
+    pub const fn new(config: PushButtonConfig) -> Self {
+        Self {
+            config,
+            state: State::Idle,
+            pressed: false,
+        }
+    }
+
+    /// Whether the button was pressed at the last update
+    pub fn is_pressed(&self) -> bool {
+        self.pressed
+    }
+
+    /// Updates with the state of the button, returning the gestures
+    pub fn update(&mut self, pressed: bool, now: Duration) -> impl Iterator<Item = ButtonEvent> {
+        self.pressed = pressed;
+        self.step(pressed, now).into_iter().flatten()
+    }
+
+    /// Updates with the last known state of the button
+    pub fn poll(&mut self, now: Duration) -> impl Iterator<Item = ButtonEvent> {
+        self.update(self.pressed, now)
+    }
+
+    /// Updates from an interrupt event, other events only advance the time
+    pub fn on_event(&mut self, event: &Event, now: Duration) -> impl Iterator<Item = ButtonEvent> {
+        let pressed = match event {
+            Event::PushButton { pressed } => *pressed,
+            _ => self.pressed,
+        };
+        self.update(pressed, now)
+    }
+
+    fn step(&mut self, pressed: bool, now: Duration) -> [Option<ButtonEvent>; 2] {
+        use ButtonEvent::*;
+
+        match (self.state, pressed) {
+            (State::Idle, false) | (State::LongPressed, true) => [None, None],
+            (State::Idle, true) => {
+                self.state = State::Pressed {
+                    since: now,
+                    second: false,
+                };
+                [Some(Pressed), None]
+            }
+            (State::Pressed { since, second }, true) => {
+                let held = now.saturating_sub(since);
+                if held >= self.config.long_press {
+                    self.state = State::LongPressed;
+                    // the first click was not followed by a second one
+                    let first = if second { Some(ShortPress) } else { None };
+                    [first, Some(LongPress(held))]
+                } else {
+                    [None, None]
+                }
+            }
+            (State::Pressed { second: true, .. }, false) => {
+                self.state = State::Idle;
+                [Some(Released), Some(DoubleClick)]
+            }
+            (State::Pressed { second: false, .. }, false) => {
+                if self.config.double_click.is_zero() {
+                    self.state = State::Idle;
+                    [Some(Released), Some(ShortPress)]
+                } else {
+                    self.state = State::WaitSecond { released_at: now };
+                    [Some(Released), None]
+                }
+            }
+            (State::LongPressed, false) => {
+                self.state = State::Idle;
+                [Some(Released), None]
+            }
+            (State::WaitSecond { released_at }, pressed) => {
+                let expired = now.saturating_sub(released_at) > self.config.double_click;
+                match (expired, pressed) {
+                    (false, false) => [None, None],
+                    (false, true) => {
+                        self.state = State::Pressed {
+                            since: now,
+                            second: true,
+                        };
+                        [Some(Pressed), None]
+                    }
+                    (true, false) => {
+                        self.state = State::Idle;
+                        [Some(ShortPress), None]
+                    }
+                    (true, true) => {
+                        self.state = State::Pressed {
+                            since: now,
+                            second: false,
+                        };
+                        [Some(ShortPress), Some(Pressed)]
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Default for PushButton {
+    fn default() -> Self {
+        Self::new(PushButtonConfig::default())
+    }
+}
+
+impl<I2C, I2CE> TPS6572x<I2C, I2CE>
+where
+    I2C: I2c<SevenBitAddress, Error = I2CE>,
+    I2CE: I2cError,
+{
+    /// Whether the push button is pressed, PB_STAT
+    pub fn push_button_pressed(&mut self) -> Result<bool, Error<I2CE>> {
+        Ok(self.read_register::<Control1>()?.push_button_pressed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::{vec, vec::Vec};
+
+    use super::*;
+    use ButtonEvent::*;
+
+    fn update(button: &mut PushButton, pressed: bool, ms: u64) -> Vec<ButtonEvent> {
+        button.update(pressed, Duration::from_millis(ms)).collect()
+    }
+
+    fn poll(button: &mut PushButton, ms: u64) -> Vec<ButtonEvent> {
+        button.poll(Duration::from_millis(ms)).collect()
+    }
+
+    #[test]
+    fn click() {
+        let mut button = PushButton::default();
+        assert_eq!(update(&mut button, true, 0), vec![Pressed]);
+        assert_eq!(update(&mut button, false, 100), vec![Released]);
+        assert_eq!(poll(&mut button, 300), vec![]);
+        assert_eq!(poll(&mut button, 401), vec![ShortPress]);
+        assert_eq!(poll(&mut button, 1000), vec![]);
+    }
+
+    #[test]
+    fn click_without_double_click() {
+        let mut button = PushButton::new(PushButtonConfig {
+            double_click: Duration::ZERO,
+            ..PushButtonConfig::default()
+        });
+        assert_eq!(update(&mut button, true, 0), vec![Pressed]);
+        assert_eq!(update(&mut button, false, 100), vec![Released, ShortPress]);
+    }
+
+    #[test]
+    fn double_click() {
+        let mut button = PushButton::default();
+        assert_eq!(update(&mut button, true, 0), vec![Pressed]);
+        assert_eq!(update(&mut button, false, 100), vec![Released]);
+        assert_eq!(update(&mut button, true, 300), vec![Pressed]);
+        assert_eq!(update(&mut button, false, 400), vec![Released, DoubleClick]);
+        assert_eq!(poll(&mut button, 2000), vec![]);
+    }
+
+    #[test]
+    fn long_press() {
+        let mut button = PushButton::default();
+        assert_eq!(update(&mut button, true, 0), vec![Pressed]);
+        assert_eq!(poll(&mut button, 999), vec![]);
+        assert_eq!(
+            poll(&mut button, 1200),
+            vec![LongPress(Duration::from_millis(1200))]
+        );
+        assert_eq!(poll(&mut button, 3000), vec![]);
+        assert_eq!(update(&mut button, false, 3100), vec![Released]);
+        assert_eq!(poll(&mut button, 5000), vec![]);
+    }
+
+    #[test]
+    fn second_press_held_into_a_long_press() {
+        let mut button = PushButton::default();
+        assert_eq!(update(&mut button, true, 0), vec![Pressed]);
+        assert_eq!(update(&mut button, false, 100), vec![Released]);
+        assert_eq!(update(&mut button, true, 200), vec![Pressed]);
+        assert_eq!(
+            poll(&mut button, 1200),
+            vec![ShortPress, LongPress(Duration::from_secs(1))]
+        );
+        assert_eq!(update(&mut button, false, 1300), vec![Released]);
+    }
+
+    #[test]
+    fn double_click_window_expiry() {
+        let mut button = PushButton::default();
+        assert_eq!(update(&mut button, true, 0), vec![Pressed]);
+        assert_eq!(update(&mut button, false, 100), vec![Released]);
+        // pressed again after the window, without a poll in between
+        assert_eq!(update(&mut button, true, 500), vec![ShortPress, Pressed]);
+        assert_eq!(update(&mut button, false, 600), vec![Released]);
+        assert_eq!(poll(&mut button, 1000), vec![ShortPress]);
+    }
+
+    #[test]
+    fn other_events_only_advance_the_time() {
+        let mut button = PushButton::default();
+        let at = Duration::from_millis;
+        let press = Event::PushButton { pressed: true };
+        let events: Vec<_> = button.on_event(&press, at(0)).collect();
+        assert_eq!(events, vec![Pressed]);
+        assert!(button.is_pressed());
+
+        let events: Vec<_> = button
+            .on_event(&Event::LowBattery { low: true }, at(1500))
+            .collect();
+        assert_eq!(events, vec![LongPress(at(1500))]);
+        assert!(button.is_pressed());
+    }
+}
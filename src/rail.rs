@@ -0,0 +1,71 @@
+use embedded_hal::i2c::{blocking::I2c, Error as I2cError, SevenBitAddress};
+
+use crate::{error::Error, registers::*, TPS6572x};
+
+/// Output rails of the chip
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Rail {
+    /// DCDC1 step down converter
+    Dcdc1,
+
+    /// LDO1 linear regulator
+    Ldo1,
+}
+
+impl Rail {
+    /// Whether the rail is in regulation, the PGOODZ bits of [`Control0`] are active low
+    pub fn power_good(self, control0: Control0) -> bool {
+        match self {
+            Rail::Dcdc1 => !control0.good_dcdc_range(),
+            Rail::Ldo1 => !control0.good_ldo_range(),
+        }
+    }
+}
+
+impl<I2C, I2CE> TPS6572x<I2C, I2CE>
+where
+    I2C: I2c<SevenBitAddress, Error = I2CE>,
+    I2CE: I2cError,
+{
+    /// Output voltage of the rail
+    pub fn rail_voltage(&mut self, rail: Rail) -> Result<OutputVoltage, Error<I2CE>> {
+        Ok(match rail {
+            Rail::Dcdc1 => self.read_register::<DCDCSetting>()?.output_voltage(),
+            Rail::Ldo1 => self.read_register::<LDOControl>()?.output_voltage(),
+        })
+    }
+
+    /// Sets the output voltage of the rail
+    pub fn set_rail_voltage(
+        &mut self,
+        rail: Rail,
+        voltage: OutputVoltage,
+    ) -> Result<(), Error<I2CE>> {
+        match rail {
+            Rail::Dcdc1 => self.edit_register(|r: DCDCSetting| r.with_output_voltage(voltage)),
+            Rail::Ldo1 => self.edit_register(|r: LDOControl| r.with_output_voltage(voltage)),
+        }
+    }
+
+    /// Whether the HOLD bit of the rail is set, keeping it enabled
+    pub fn rail_hold(&mut self, rail: Rail) -> Result<bool, Error<I2CE>> {
+        Ok(match rail {
+            Rail::Dcdc1 => self.read_register::<DCDCSetting>()?.hold(),
+            Rail::Ldo1 => self.read_register::<LDOControl>()?.hold(),
+        })
+    }
+
+    /// Sets or clears the HOLD bit of the rail
+    pub fn set_rail_hold(&mut self, rail: Rail, hold: bool) -> Result<(), Error<I2CE>> {
+        match rail {
+            Rail::Dcdc1 => self.edit_register(|r: DCDCSetting| r.with_hold(hold)),
+            Rail::Ldo1 => self.edit_register(|r: LDOControl| r.with_hold(hold)),
+        }
+    }
+
+    /// Whether the rail is in regulation
+    pub fn is_power_good(&mut self, rail: Rail) -> Result<bool, Error<I2CE>> {
+        Ok(rail.power_good(self.read_register()?))
+    }
+}
@@ -0,0 +1,234 @@
+use core::time::Duration;
+use embedded_hal::{
+    delay::blocking::DelayUs,
+    i2c::{blocking::I2c, Error as I2cError, SevenBitAddress},
+};
+
+use crate::{delay_for, error::Error, rail::Rail, registers::OutputVoltage, TPS6572x};
+
+/// Intermediate output voltages when going from one voltage to another
+///
+/// Each step changes the voltage by at most the step, but at least by one code, and the
+/// last step is the target voltage. The steps are independent of the bus so they can be
+/// used by the blocking and async drivers.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct VoltageRamp {
+    current: OutputVoltage,
+    target: OutputVoltage,
+    step_mv: u16,
+}
+
+impl VoltageRamp {
+    pub fn new(from: OutputVoltage, to: OutputVoltage, step_mv: u16) -> Self {
+        Self {
+            current: from,
+            target: to,
+            step_mv,
+        }
+    }
+}
+
+impl Iterator for VoltageRamp {
+    type Item = OutputVoltage;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.current as u8;
+        let target = self.target as u8;
+        let millivolts = |code| OutputVoltage::from_code(code).millivolts();
+
+        let next = if current < target {
+            let limit = self.current.millivolts().saturating_add(self.step_mv);
+            (current + 1..=target)
+                .take_while(|&code| millivolts(code) <= limit)
+                .last()
+                .unwrap_or(current + 1)
+        } else if current > target {
+            let limit = self.current.millivolts().saturating_sub(self.step_mv);
+            (target..current)
+                .rev()
+                .take_while(|&code| millivolts(code) >= limit)
+                .last()
+                .unwrap_or(current - 1)
+        } else {
+            return None;
+        };
+
+        self.current = OutputVoltage::from_code(next);
+        Some(self.current)
+    }
+}
+
+/// Output voltage for the millivolts, erroring if out of the supported range
+pub(crate) fn output_voltage<I2CE>(millivolts: u16) -> Result<OutputVoltage, Error<I2CE>> {
+    OutputVoltage::from_millivolts(millivolts).ok_or(Error::OutOfRange {
+        value: millivolts.into(),
+        min: OutputVoltage::MIN_MILLIVOLTS.into(),
+        max: OutputVoltage::MAX_MILLIVOLTS.into(),
+    })
+}
+
+/// Target output voltage of a ramp, erroring if out of range or if the step is zero
+pub(crate) fn ramp_target<I2CE>(
+    millivolts: u16,
+    step_mv: u16,
+) -> Result<OutputVoltage, Error<I2CE>> {
+    let target = output_voltage(millivolts)?;
+    if step_mv == 0 {
+        return Err(Error::OutOfRange {
+            value: 0,
+            min: 1,
+            max: OutputVoltage::MAX_MILLIVOLTS.into(),
+        });
+    }
+    Ok(target)
+}
+
+impl<I2C, I2CE> TPS6572x<I2C, I2CE>
+where
+    I2C: I2c<SevenBitAddress, Error = I2CE>,
+    I2CE: I2cError,
+{
+    /// Ramps the DCDC1 output to the millivolts, see [`TPS6572x::ramp_rail_to`]
+    pub fn ramp_dcdc1_to<D>(
+        &mut self,
+        millivolts: u16,
+        step_mv: u16,
+        step_delay: Duration,
+        delay: &mut D,
+    ) -> Result<OutputVoltage, Error<I2CE>>
+    where
+        D: DelayUs,
+    {
+        self.ramp_rail_to(Rail::Dcdc1, millivolts, step_mv, step_delay, delay)
+    }
+
+    /// Ramps the LDO1 output to the millivolts, see [`TPS6572x::ramp_rail_to`]
+    pub fn ramp_ldo1_to<D>(
+        &mut self,
+        millivolts: u16,
+        step_mv: u16,
+        step_delay: Duration,
+        delay: &mut D,
+    ) -> Result<OutputVoltage, Error<I2CE>>
+    where
+        D: DelayUs,
+    {
+        self.ramp_rail_to(Rail::Ldo1, millivolts, step_mv, step_delay, delay)
+    }
+
+    /// Ramps the output of the rail to the millivolts by steps of at most `step_mv`
+    ///
+    /// The millivolts are rounded down to a supported output voltage, which is returned.
+    /// If the rail is in regulation when starting, the ramp is aborted with
+    /// [`Error::PowerGoodLost`] as soon as it drops out of regulation.
+    pub fn ramp_rail_to<D>(
+        &mut self,
+        rail: Rail,
+        millivolts: u16,
+        step_mv: u16,
+        step_delay: Duration,
+        delay: &mut D,
+    ) -> Result<OutputVoltage, Error<I2CE>>
+    where
+        D: DelayUs,
+    {
+        let target = ramp_target(millivolts, step_mv)?;
+
+        let check_power_good = self.is_power_good(rail)?;
+        let from = self.rail_voltage(rail)?;
+        for voltage in VoltageRamp::new(from, target, step_mv) {
+            self.set_rail_voltage(rail, voltage)?;
+            delay_for(delay, step_delay)?;
+
+            if check_power_good && !self.is_power_good(rail)? {
+                return Err(Error::PowerGoodLost { rail, voltage });
+            }
+        }
+        Ok(target)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::{vec, vec::Vec};
+
+    use super::*;
+
+    fn ramp(from: OutputVoltage, to: OutputVoltage, step_mv: u16) -> Vec<u16> {
+        VoltageRamp::new(from, to, step_mv)
+            .map(|voltage| voltage.millivolts())
+            .collect()
+    }
+
+    #[test]
+    fn ramps_up_and_down_to_the_target() {
+        use OutputVoltage::*;
+        assert_eq!(ramp(V1_000, V1_300, 100), vec![1100, 1200, 1300]);
+        assert_eq!(ramp(V1_300, V1_000, 100), vec![1200, 1100, 1000]);
+        assert_eq!(ramp(V1_000, V1_300, 120), vec![1100, 1200, 1300]);
+        assert_eq!(ramp(V1_200, V3_300, 5000), vec![3300]);
+        assert_eq!(ramp(V3_300, V1_200, 5000), vec![1200]);
+    }
+
+    #[test]
+    fn steps_across_the_code_sizes() {
+        use OutputVoltage::*;
+        assert_eq!(ramp(V1_550, V1_750, 75), vec![1600, 1650, 1700, 1750]);
+        assert_eq!(ramp(V1_750, V1_550, 75), vec![1700, 1650, 1575, 1550]);
+    }
+
+    #[test]
+    fn steps_by_at_least_one_code() {
+        use OutputVoltage::*;
+        assert_eq!(ramp(V1_000, V1_100, 10), vec![1025, 1050, 1075, 1100]);
+        assert_eq!(ramp(V1_100, V1_000, 1), vec![1075, 1050, 1025, 1000]);
+    }
+
+    #[test]
+    fn no_steps_at_the_target() {
+        assert_eq!(
+            ramp(OutputVoltage::V1_800, OutputVoltage::V1_800, 100),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn out_of_range_output_voltage() {
+        assert!(matches!(
+            output_voltage::<()>(799),
+            Err(Error::OutOfRange {
+                value: 799,
+                min: 800,
+                max: 3300
+            })
+        ));
+        assert!(matches!(
+            output_voltage::<()>(3301),
+            Err(Error::OutOfRange { .. })
+        ));
+        assert_eq!(output_voltage::<()>(1630).ok(), Some(OutputVoltage::V1_600));
+    }
+
+    #[test]
+    fn ramp_target_needs_a_step() {
+        assert_eq!(
+            ramp_target::<()>(1800, 25).ok(),
+            Some(OutputVoltage::V1_800)
+        );
+        assert!(matches!(
+            ramp_target::<()>(1800, 0),
+            Err(Error::OutOfRange {
+                value: 0,
+                min: 1,
+                ..
+            })
+        ));
+        assert!(matches!(
+            ramp_target::<()>(5000, 0),
+            Err(Error::OutOfRange { value: 5000, .. })
+        ));
+    }
+}
@@ -1,3 +1,4 @@
+use core::time::Duration;
 use modular_bitfield::prelude::*;
 use register_macros::{RORegister, RWRegister};
 
@@ -46,13 +47,13 @@ pub enum RegisterAddress {
     GPIOConfig = 0x0C,
 
     /// IRMARSK0
-    InterruptMask0 = 0xD0,
+    InterruptMask0 = 0x0D,
 
     /// IRMASK1
-    InterruptMask1 = 0xE0,
+    InterruptMask1 = 0x0E,
 
     /// IRMASK2
-    InterruptMask2 = 0xF0,
+    InterruptMask2 = 0x0F,
 
     /// IR0
     Interrupt0 = 0x10,
@@ -104,6 +105,7 @@ pub struct ChargerStatus {
 }
 
 /// Represents the possible output voltage for the `[ChargerConfig0]`
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[derive(Copy, Clone, PartialEq, Debug, BitfieldSpecifier)]
 #[bits = 2]
 pub enum SysOutputVoltage {
@@ -120,7 +122,20 @@ pub enum SysOutputVoltage {
     V5_5 = 0b11,
 }
 
+impl SysOutputVoltage {
+    /// Regulated SYS voltage in millivolts, `None` when SYS follows the power path
+    pub fn millivolts(self) -> Option<u16> {
+        match self {
+            SysOutputVoltage::Vsys => None,
+            SysOutputVoltage::V4_4 => Some(4400),
+            SysOutputVoltage::V5 => Some(5000),
+            SysOutputVoltage::V5_5 => Some(5500),
+        }
+    }
+}
+
 /// Represents the possible input current for the `[ChargerConfig0]`
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[derive(Copy, Clone, PartialEq, Debug, BitfieldSpecifier)]
 #[bits = 2]
 pub enum AcInputCurrent {
@@ -163,6 +178,7 @@ pub struct ChargerConfig0 {
 }
 
 /// Termination current scaling factor
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[derive(Copy, Clone, PartialEq, Debug, BitfieldSpecifier)]
 #[bits = 2]
 pub enum TerminationCurrentScalingFactor {
@@ -173,6 +189,7 @@ pub enum TerminationCurrentScalingFactor {
 }
 
 /// Charge current factor
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[derive(Copy, Clone, PartialEq, Debug, BitfieldSpecifier)]
 #[bits = 2]
 pub enum ChargeCurrentFactor {
@@ -183,6 +200,7 @@ pub enum ChargeCurrentFactor {
 }
 
 /// Pre charge current factor
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[derive(Copy, Clone, PartialEq, Debug, BitfieldSpecifier)]
 #[bits = 2]
 pub enum PreChargeCurrentFactor {
@@ -196,6 +214,7 @@ pub enum PreChargeCurrentFactor {
 #[bitfield]
 #[repr(u8)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, PartialEq, Debug, RWRegister)]
 pub struct ChargerConfig1 {
     /// Skip B0 and B1
     #[skip]
@@ -212,6 +231,7 @@ pub struct ChargerConfig1 {
 }
 
 /// PowerPath threshold
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[derive(Copy, Clone, PartialEq, Debug, BitfieldSpecifier)]
 #[bits = 1]
 pub enum PowerBatThreshold {
@@ -220,6 +240,7 @@ pub enum PowerBatThreshold {
 }
 
 /// NTC sensor resistance
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[derive(Copy, Clone, PartialEq, Debug, BitfieldSpecifier)]
 #[bits = 1]
 pub enum NTC {
@@ -228,6 +249,7 @@ pub enum NTC {
 }
 
 /// Pre-charge timer value
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[derive(Copy, Clone, PartialEq, Debug, BitfieldSpecifier)]
 #[bits = 1]
 pub enum PreChargeTimer {
@@ -235,7 +257,25 @@ pub enum PreChargeTimer {
     Min60 = 0b1,
 }
 
+impl PreChargeTimer {
+    /// Duration of the timer
+    pub fn duration(self) -> Duration {
+        match self {
+            PreChargeTimer::Min30 => Duration::from_secs(30 * 60),
+            PreChargeTimer::Min60 => Duration::from_secs(60 * 60),
+        }
+    }
+
+    /// Timer of the duration, `None` if not supported
+    pub fn from_duration(duration: Duration) -> Option<Self> {
+        [PreChargeTimer::Min30, PreChargeTimer::Min60]
+            .into_iter()
+            .find(|t| t.duration() == duration)
+    }
+}
+
 /// Charge safety timer
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[derive(Copy, Clone, PartialEq, Debug, BitfieldSpecifier)]
 #[bits = 2]
 pub enum SafetyChargeTimer {
@@ -245,6 +285,31 @@ pub enum SafetyChargeTimer {
     Hour8 = 0b11,
 }
 
+impl SafetyChargeTimer {
+    /// Duration of the timer
+    pub fn duration(self) -> Duration {
+        let hours = match self {
+            SafetyChargeTimer::Hour4 => 4,
+            SafetyChargeTimer::Hour5 => 5,
+            SafetyChargeTimer::Hour6 => 6,
+            SafetyChargeTimer::Hour8 => 8,
+        };
+        Duration::from_secs(hours * 60 * 60)
+    }
+
+    /// Timer of the duration, `None` if not supported
+    pub fn from_duration(duration: Duration) -> Option<Self> {
+        [
+            SafetyChargeTimer::Hour4,
+            SafetyChargeTimer::Hour5,
+            SafetyChargeTimer::Hour6,
+            SafetyChargeTimer::Hour8,
+        ]
+        .into_iter()
+        .find(|t| t.duration() == duration)
+    }
+}
+
 /// CHGCONFIG2 register
 #[bitfield]
 #[repr(u8)]
@@ -276,6 +341,7 @@ pub struct ChargerConfig2 {
 }
 
 /// Battery voltage comparator threshold
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[derive(Copy, Clone, PartialEq, Debug, BitfieldSpecifier)]
 #[bits = 2]
 pub enum BatteryVoltageTreshold {
@@ -285,7 +351,23 @@ pub enum BatteryVoltageTreshold {
     V2_5 = 0b11,
 }
 
+impl BatteryVoltageTreshold {
+    /// Threshold in millivolts
+    pub fn millivolts(self) -> u16 {
+        2200 + 100 * self as u16
+    }
+
+    /// Threshold nearest to the millivolts
+    pub fn nearest(millivolts: u16) -> Self {
+        [Self::V2_2, Self::V2_3, Self::V2_4, Self::V2_5]
+            .into_iter()
+            .min_by_key(|t| (i32::from(t.millivolts()) - i32::from(millivolts)).abs())
+            .unwrap_or(Self::V2_2)
+    }
+}
+
 /// Battery temperature shift
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[derive(Copy, Clone, PartialEq, Debug, BitfieldSpecifier)]
 #[bits = 2]
 pub enum BatteryTemperatureShift {
@@ -296,6 +378,7 @@ pub enum BatteryTemperatureShift {
 }
 
 /// Charge voltage selection
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[derive(Copy, Clone, PartialEq, Debug, BitfieldSpecifier)]
 #[bits = 3]
 pub enum ChargeVoltage {
@@ -309,6 +392,13 @@ pub enum ChargeVoltage {
     V4_325 = 0b111,
 }
 
+impl ChargeVoltage {
+    /// Charge voltage in millivolts
+    pub fn millivolts(self) -> u16 {
+        4150 + 25 * self as u16
+    }
+}
+
 /// CHGCONFIG3 register
 #[bitfield]
 #[repr(u8)]
@@ -369,6 +459,7 @@ pub struct ChargerState {
 }
 
 /// Charge voltage selection
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[derive(Copy, Clone, PartialEq, Debug, BitfieldSpecifier)]
 #[bits = 6]
 pub enum OutputVoltage {
@@ -438,6 +529,40 @@ pub enum OutputVoltage {
     V3_300 = 63,
 }
 
+impl OutputVoltage {
+    /// Lowest output voltage, in millivolts
+    pub const MIN_MILLIVOLTS: u16 = 800;
+
+    /// Highest output voltage, in millivolts
+    pub const MAX_MILLIVOLTS: u16 = 3300;
+
+    /// Output voltage in millivolts
+    pub fn millivolts(self) -> u16 {
+        let code = self as u16;
+        match code {
+            0..=32 => 800 + 25 * code,
+            33..=60 => 1600 + 50 * (code - 32),
+            _ => 3000 + 100 * (code - 60),
+        }
+    }
+
+    /// Highest output voltage not above the millivolts, `None` if out of the supported range
+    pub fn from_millivolts(millivolts: u16) -> Option<Self> {
+        if !(Self::MIN_MILLIVOLTS..=Self::MAX_MILLIVOLTS).contains(&millivolts) {
+            return None;
+        }
+        (0..=63)
+            .map(Self::from_code)
+            .take_while(|v| v.millivolts() <= millivolts)
+            .last()
+    }
+
+    /// Output voltage of the 6 bits code
+    pub(crate) fn from_code(code: u8) -> Self {
+        <Self as Specifier>::from_bytes(code & 0b11_1111).unwrap_or(OutputVoltage::V0_800)
+    }
+}
+
 /// DEFDCDC1
 #[bitfield]
 #[repr(u8)]
@@ -493,6 +618,7 @@ pub struct Control0 {
 }
 
 /// Reset delay
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[derive(Copy, Clone, PartialEq, Debug, BitfieldSpecifier)]
 #[bits = 1]
 pub enum ResetDelay {
@@ -500,7 +626,25 @@ pub enum ResetDelay {
     Ms90 = 0b1,
 }
 
+impl ResetDelay {
+    /// Duration of the delay
+    pub fn duration(self) -> Duration {
+        match self {
+            ResetDelay::Ms11 => Duration::from_millis(11),
+            ResetDelay::Ms90 => Duration::from_millis(90),
+        }
+    }
+
+    /// Delay of the duration, `None` if not supported
+    pub fn from_duration(duration: Duration) -> Option<Self> {
+        [ResetDelay::Ms11, ResetDelay::Ms90]
+            .into_iter()
+            .find(|d| d.duration() == duration)
+    }
+}
+
 /// Reset delay
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[derive(Copy, Clone, PartialEq, Debug, BitfieldSpecifier)]
 #[bits = 1]
 pub enum OpampMuxMeasurement {
@@ -567,6 +711,7 @@ pub struct Control1 {
 }
 
 /// GPIO mode
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[derive(Copy, Clone, PartialEq, Debug, BitfieldSpecifier)]
 #[bits = 1]
 pub enum GPIOPull {
@@ -601,6 +746,7 @@ pub struct GPIOSSC {
 }
 
 /// GPIO input/output mode
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[derive(Copy, Clone, PartialEq, Debug, BitfieldSpecifier)]
 #[bits = 1]
 pub enum GPIOMode {
@@ -609,6 +755,7 @@ pub enum GPIOMode {
 }
 
 /// GPIO drive
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[derive(Copy, Clone, PartialEq, Debug, BitfieldSpecifier)]
 #[bits = 1]
 pub enum GPIODrive {
@@ -644,6 +791,7 @@ pub struct GPIOConfig {
     pub gpio3_led: GPIODrive,
 }
 
+/// IRMASK0 register, a set bit masks the interrupt
 #[bitfield]
 #[repr(u8)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
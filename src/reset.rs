@@ -0,0 +1,63 @@
+use core::time::Duration;
+use embedded_hal::i2c::{blocking::I2c, Error as I2cError, SevenBitAddress};
+
+use crate::{
+    error::Error,
+    registers::{Control1, ResetDelay},
+    TPS6572x,
+};
+
+impl<I2C, I2CE> TPS6572x<I2C, I2CE>
+where
+    I2C: I2c<SevenBitAddress, Error = I2CE>,
+    I2CE: I2cError,
+{
+    /// Delay of the reset output after the rails are up
+    pub fn reset_delay(&mut self) -> Result<Duration, Error<I2CE>> {
+        Ok(self.read_register::<Control1>()?.reset_delay().duration())
+    }
+
+    /// Sets the delay of the reset output, only 11 ms and 90 ms are supported
+    pub fn set_reset_delay(&mut self, duration: Duration) -> Result<(), Error<I2CE>> {
+        let reset_delay = reset_delay(duration)?;
+        self.edit_register(|r: Control1| r.with_reset_delay(reset_delay))
+    }
+}
+
+/// Reset delay of the duration, in µs in the error if not supported
+pub(crate) fn reset_delay<I2CE>(duration: Duration) -> Result<ResetDelay, Error<I2CE>> {
+    let micros = |d: Duration| i32::try_from(d.as_micros()).unwrap_or(i32::MAX);
+
+    ResetDelay::from_duration(duration).ok_or_else(|| {
+        Error::unsupported(
+            micros(duration),
+            micros(ResetDelay::Ms11.duration()),
+            micros(ResetDelay::Ms90.duration()),
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unsupported_delays_are_reported_in_micros() {
+        let error = |duration| match reset_delay::<()>(duration) {
+            Err(Error::UnsupportedValue { value }) => Some((value, 0, 0)),
+            Err(Error::OutOfRange { value, min, max }) => Some((value, min, max)),
+            _ => None,
+        };
+        assert_eq!(error(Duration::from_millis(11)), None);
+        assert_eq!(error(Duration::from_millis(90)), None);
+        assert_eq!(error(Duration::from_micros(11_500)), Some((11_500, 0, 0)));
+        assert_eq!(
+            error(Duration::from_micros(90_400)),
+            Some((90_400, 11_000, 90_000))
+        );
+        assert_eq!(
+            error(Duration::from_secs(3600)),
+            Some((i32::MAX, 11_000, 90_000))
+        );
+    }
+}
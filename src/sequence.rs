@@ -0,0 +1,182 @@
+use core::time::Duration;
+use embedded_hal::{
+    delay::blocking::DelayUs,
+    i2c::{blocking::I2c, Error as I2cError, SevenBitAddress},
+};
+
+use crate::{delay_for, error::Error, rail::Rail, registers::OutputVoltage, TPS6572x};
+
+/// Interval between two reads of the power good bits
+const POWER_GOOD_POLL: Duration = Duration::from_millis(1);
+
+/// Step of a [`PowerSequence`]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Step {
+    /// Sets the output voltage of the rail
+    SetVoltage(Rail, OutputVoltage),
+
+    /// Sets or clears the HOLD bit of the rail
+    SetHold(Rail, bool),
+
+    /// Waits until the rail is in regulation, fails with [`Error::Timeout`] after the duration
+    WaitPowerGood(Rail, Duration),
+
+    /// Waits for the duration
+    Delay(Duration),
+
+    /// Sets the delay of the reset output after the rails are up, 11 ms or 90 ms
+    SetResetDelay(Duration),
+}
+
+impl Step {
+    /// Step undoing this one in the reverse sequence, if any
+    pub fn reversed(self) -> Option<Step> {
+        match self {
+            Step::SetHold(rail, hold) => Some(Step::SetHold(rail, !hold)),
+            Step::Delay(duration) => Some(Step::Delay(duration)),
+            Step::SetVoltage(..) | Step::WaitPowerGood(..) | Step::SetResetDelay(..) => None,
+        }
+    }
+}
+
+/// Ordered steps to power up the rails, holding up to `N` steps
+///
+/// ```
+/// use core::time::Duration;
+/// use tps6572x::{rail::Rail, registers::OutputVoltage, sequence::PowerSequence};
+///
+/// const STARTUP: PowerSequence<6> = PowerSequence::new()
+///     .set_voltage(Rail::Dcdc1, OutputVoltage::V1_800)
+///     .set_hold(Rail::Dcdc1, true)
+///     .wait_power_good(Rail::Dcdc1, Duration::from_millis(10))
+///     .delay(Duration::from_millis(2))
+///     .set_voltage(Rail::Ldo1, OutputVoltage::V3_300)
+///     .set_hold(Rail::Ldo1, true);
+///
+/// // Drops LDO1, waits, then drops DCDC1
+/// let shutdown = STARTUP.reversed();
+/// ```
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct PowerSequence<const N: usize> {
+    steps: [Option<Step>; N],
+    len: usize,
+}
+
+impl<const N: usize> PowerSequence<N> {
+    pub const fn new() -> Self {
+        Self {
+            steps: [None; N],
+            len: 0,
+        }
+    }
+
+    /// Appends the step
+    ///
+    /// # Panics
+    /// If the sequence already holds `N` steps
+    pub const fn then(mut self, step: Step) -> Self {
+        assert!(self.len < N, "power sequence is full");
+        self.steps[self.len] = Some(step);
+        self.len += 1;
+        self
+    }
+
+    pub const fn set_voltage(self, rail: Rail, voltage: OutputVoltage) -> Self {
+        self.then(Step::SetVoltage(rail, voltage))
+    }
+
+    pub const fn set_hold(self, rail: Rail, hold: bool) -> Self {
+        self.then(Step::SetHold(rail, hold))
+    }
+
+    pub const fn wait_power_good(self, rail: Rail, timeout: Duration) -> Self {
+        self.then(Step::WaitPowerGood(rail, timeout))
+    }
+
+    pub const fn delay(self, duration: Duration) -> Self {
+        self.then(Step::Delay(duration))
+    }
+
+    pub const fn set_reset_delay(self, duration: Duration) -> Self {
+        self.then(Step::SetResetDelay(duration))
+    }
+
+    /// Steps of the sequence, in order
+    pub fn steps(&self) -> impl DoubleEndedIterator<Item = Step> + '_ {
+        self.steps[..self.len].iter().flatten().copied()
+    }
+
+    /// Shutdown sequence, undoing the steps in the reverse order
+    ///
+    /// HOLD bits are inverted and delays kept, the other steps are dropped.
+    pub fn reversed(&self) -> Self {
+        let mut reversed = Self::new();
+        for step in self.steps().rev().filter_map(Step::reversed) {
+            reversed = reversed.then(step);
+        }
+        reversed
+    }
+}
+
+impl<const N: usize> Default for PowerSequence<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<I2C, I2CE> TPS6572x<I2C, I2CE>
+where
+    I2C: I2c<SevenBitAddress, Error = I2CE>,
+    I2CE: I2cError,
+{
+    /// Runs the steps of the sequence in order, stopping at the first error
+    pub fn run_sequence<D, const N: usize>(
+        &mut self,
+        sequence: &PowerSequence<N>,
+        delay: &mut D,
+    ) -> Result<(), Error<I2CE>>
+    where
+        D: DelayUs,
+    {
+        sequence
+            .steps()
+            .try_for_each(|step| self.run_step(step, delay))
+    }
+
+    /// Runs a single step of a sequence
+    pub fn run_step<D>(&mut self, step: Step, delay: &mut D) -> Result<(), Error<I2CE>>
+    where
+        D: DelayUs,
+    {
+        match step {
+            Step::SetVoltage(rail, voltage) => self.set_rail_voltage(rail, voltage),
+            Step::SetHold(rail, hold) => self.set_rail_hold(rail, hold),
+            Step::WaitPowerGood(rail, timeout) => self.wait_power_good(rail, timeout, delay),
+            Step::Delay(duration) => delay_for(delay, duration),
+            Step::SetResetDelay(duration) => self.set_reset_delay(duration),
+        }
+    }
+
+    /// Waits until the rail is in regulation, fails with [`Error::Timeout`] after the timeout
+    pub fn wait_power_good<D>(
+        &mut self,
+        rail: Rail,
+        timeout: Duration,
+        delay: &mut D,
+    ) -> Result<(), Error<I2CE>>
+    where
+        D: DelayUs,
+    {
+        let mut elapsed = Duration::ZERO;
+        while !self.is_power_good(rail)? {
+            if elapsed >= timeout {
+                return Err(Error::Timeout);
+            }
+            delay_for(delay, POWER_GOOD_POLL)?;
+            elapsed += POWER_GOOD_POLL;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,270 @@
+use core::time::Duration;
+
+use crate::{
+    charger::ChargePhase,
+    registers::{ChargerState, ChargerStatus},
+};
+
+/// Why a charge session ended
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum TerminationReason {
+    /// Charge terminated, or charger disabled, while charging
+    Complete,
+
+    /// Charger stopped on a fault
+    Fault,
+
+    /// Charger suspended, e.g. battery temperature out of the window
+    Suspended,
+
+    /// Input power removed
+    InputRemoved,
+}
+
+/// Statistics of a charge session
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub struct ChargeSummary {
+    pub precharge: Duration,
+    pub constant_current: Duration,
+    pub constant_voltage: Duration,
+
+    /// Time from the start to the end of the session, including suspended and faulted time
+    pub total: Duration,
+
+    pub faults: u16,
+    pub thermal_loop_activations: u16,
+
+    /// `None` while the session is running
+    pub termination: Option<TerminationReason>,
+}
+
+/// Tracks the charge sessions from periodic samples of the charger state
+///
+/// A session starts when the charger starts charging and ends when it goes idle or
+/// loses its input, faults and suspensions being part of the session. The chip reports
+/// constant current and constant voltage as a single state, they are told apart from
+/// the battery voltage if given with [`ChargeSession::update_with_voltage`], otherwise
+/// the whole fast charge is counted as constant current.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct ChargeSession {
+    cv_threshold_mv: u16,
+    summary: Option<ChargeSummary>,
+    last: Option<(ChargePhase, bool, Duration)>,
+    constant_voltage: bool,
+}
+
+impl ChargeSession {
+    /// Fast charge is counted as constant voltage above the battery voltage threshold
+    pub const fn new(cv_threshold_mv: u16) -> Self {
+        Self {
+            cv_threshold_mv,
+            summary: None,
+            last: None,
+            constant_voltage: false,
+        }
+    }
+
+    /// Statistics of the running session
+    pub fn current(&self) -> Option<&ChargeSummary> {
+        self.summary.as_ref()
+    }
+
+    /// Updates with a sample, returning the summary when a session ends
+    pub fn update(
+        &mut self,
+        state: ChargerState,
+        status: ChargerStatus,
+        now: Duration,
+    ) -> Option<ChargeSummary> {
+        self.sample(state.into(), status.thermal_loop_active(), now)
+    }
+
+    /// Updates with a sample and the battery voltage, returning the summary when a session ends
+    pub fn update_with_voltage(
+        &mut self,
+        state: ChargerState,
+        status: ChargerStatus,
+        battery_mv: u16,
+        now: Duration,
+    ) -> Option<ChargeSummary> {
+        let summary = self.sample(state.into(), status.thermal_loop_active(), now);
+        self.constant_voltage = battery_mv >= self.cv_threshold_mv;
+        summary
+    }
+
+    fn sample(
+        &mut self,
+        phase: ChargePhase,
+        thermal_loop: bool,
+        now: Duration,
+    ) -> Option<ChargeSummary> {
+        let last = self.last.replace((phase, thermal_loop, now));
+
+        if let (Some(summary), Some((last_phase, last_thermal_loop, at))) =
+            (&mut self.summary, last)
+        {
+            let elapsed = now.saturating_sub(at);
+            summary.total += elapsed;
+            match last_phase {
+                ChargePhase::Precharge => summary.precharge += elapsed,
+                ChargePhase::FastCharge if self.constant_voltage => {
+                    summary.constant_voltage += elapsed
+                }
+                ChargePhase::FastCharge => summary.constant_current += elapsed,
+                _ => {}
+            }
+
+            if phase == ChargePhase::Fault && last_phase != ChargePhase::Fault {
+                summary.faults = summary.faults.saturating_add(1);
+            }
+            if thermal_loop && !last_thermal_loop {
+                summary.thermal_loop_activations =
+                    summary.thermal_loop_activations.saturating_add(1);
+            }
+
+            let termination = match (last_phase, phase) {
+                (_, ChargePhase::Sleep) => Some(TerminationReason::InputRemoved),
+                (ChargePhase::Fault, ChargePhase::Idle) => Some(TerminationReason::Fault),
+                (ChargePhase::Suspended, ChargePhase::Idle) => Some(TerminationReason::Suspended),
+                (_, ChargePhase::Idle) => Some(TerminationReason::Complete),
+                _ => None,
+            };
+            if termination.is_some() {
+                summary.termination = termination;
+                self.constant_voltage = false;
+                return self.summary.take();
+            }
+        } else if phase.is_charging() {
+            self.summary = Some(ChargeSummary {
+                thermal_loop_activations: thermal_loop.into(),
+                ..ChargeSummary::default()
+            });
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SUSPENDED: u8 = 1 << 0;
+    const FAULT: u8 = 1 << 1;
+    const FAST_CHARGE: u8 = 1 << 3;
+    const PRECHARGE: u8 = 1 << 4;
+    const IDLE: u8 = 1 << 5;
+    const SLEEP: u8 = 1 << 7;
+
+    fn update(session: &mut ChargeSession, state: u8, secs: u64) -> Option<ChargeSummary> {
+        session.update(
+            ChargerState::from(state),
+            ChargerStatus::from(0),
+            Duration::from_secs(secs),
+        )
+    }
+
+    fn update_with_voltage(
+        session: &mut ChargeSession,
+        state: u8,
+        thermal_loop: bool,
+        battery_mv: u16,
+        secs: u64,
+    ) -> Option<ChargeSummary> {
+        session.update_with_voltage(
+            ChargerState::from(state),
+            ChargerStatus::from(u8::from(thermal_loop) << 1),
+            battery_mv,
+            Duration::from_secs(secs),
+        )
+    }
+
+    #[test]
+    fn complete_session() {
+        let mut session = ChargeSession::new(4100);
+        assert_eq!(update(&mut session, IDLE, 0), None);
+        assert_eq!(session.current(), None);
+
+        assert_eq!(update(&mut session, PRECHARGE, 10), None);
+        assert_eq!(
+            update_with_voltage(&mut session, FAST_CHARGE, true, 3900, 70),
+            None
+        );
+        assert_eq!(
+            update_with_voltage(&mut session, FAST_CHARGE, false, 4150, 100),
+            None
+        );
+        assert_eq!(
+            update_with_voltage(&mut session, FAST_CHARGE, true, 4180, 150),
+            None
+        );
+        assert_eq!(
+            session.current().map(|s| s.total),
+            Some(Duration::from_secs(140))
+        );
+
+        let summary = update(&mut session, IDLE, 200).unwrap();
+        assert_eq!(
+            summary,
+            ChargeSummary {
+                precharge: Duration::from_secs(60),
+                constant_current: Duration::from_secs(30),
+                constant_voltage: Duration::from_secs(100),
+                total: Duration::from_secs(190),
+                faults: 0,
+                thermal_loop_activations: 2,
+                termination: Some(TerminationReason::Complete),
+            }
+        );
+        assert_eq!(session.current(), None);
+    }
+
+    #[test]
+    fn fast_charge_without_voltage_is_constant_current() {
+        let mut session = ChargeSession::new(4100);
+        update(&mut session, FAST_CHARGE, 0);
+        let summary = update(&mut session, IDLE, 50).unwrap();
+        assert_eq!(summary.constant_current, Duration::from_secs(50));
+        assert_eq!(summary.constant_voltage, Duration::ZERO);
+    }
+
+    #[test]
+    fn terminations() {
+        let termination = |states: &[u8]| {
+            let mut session = ChargeSession::new(4100);
+            states
+                .iter()
+                .zip(0..)
+                .find_map(|(&state, secs)| update(&mut session, state, secs))
+                .and_then(|summary| summary.termination)
+        };
+        use TerminationReason::*;
+        assert_eq!(termination(&[FAST_CHARGE, IDLE]), Some(Complete));
+        assert_eq!(termination(&[FAST_CHARGE, FAULT, IDLE]), Some(Fault));
+        assert_eq!(
+            termination(&[FAST_CHARGE, SUSPENDED, IDLE]),
+            Some(Suspended)
+        );
+        assert_eq!(termination(&[PRECHARGE, SLEEP]), Some(InputRemoved));
+        assert_eq!(termination(&[FAST_CHARGE, SUSPENDED, FAST_CHARGE]), None);
+    }
+
+    #[test]
+    fn counts_faults_once_each() {
+        let mut session = ChargeSession::new(4100);
+        for (secs, state) in [FAST_CHARGE, FAULT, FAULT, FAST_CHARGE, FAULT]
+            .into_iter()
+            .enumerate()
+        {
+            assert_eq!(update(&mut session, state, secs as u64), None);
+        }
+        let summary = update(&mut session, IDLE, 10).unwrap();
+        assert_eq!(summary.faults, 2);
+        assert_eq!(summary.total, Duration::from_secs(10));
+        assert_eq!(summary.termination, Some(TerminationReason::Fault));
+    }
+}
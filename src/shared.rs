@@ -0,0 +1,103 @@
+use embedded_hal::i2c::{blocking::I2c, Error as I2cError, SevenBitAddress};
+
+use crate::{error::Error, registers::*, TPS6572x, TPS_ADDRESS};
+
+/// Driver for a TPS6572x on a bus that is only borrowed for each operation
+///
+/// Only the configuration of the driver is kept, so the bus stays free for the other
+/// devices between operations. Works with any handle on the bus implementing `I2c`,
+/// e.g. `RefCell` or critical section based shared-bus wrappers.
+///
+/// ```
+/// use embedded_hal::i2c::{blocking::I2c, Error, SevenBitAddress};
+/// use tps6572x::{registers::ChargerConfig0, shared::SharedTPS6572x};
+///
+/// const PMIC: SharedTPS6572x = SharedTPS6572x::new();
+///
+/// fn enable_charger<I2C, E>(bus: &mut I2C) -> Result<(), tps6572x::error::Error<E>>
+/// where
+///     I2C: I2c<SevenBitAddress, Error = E>,
+///     E: Error,
+/// {
+///     PMIC.edit_register(bus, |r: ChargerConfig0| r.with_charger_enabled(true))?;
+///
+///     // Higher level operations borrow the bus through a temporary driver
+///     let _status = PMIC.attach(bus).check_presence();
+///     Ok(())
+/// }
+/// ```
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct SharedTPS6572x {
+    address: SevenBitAddress,
+}
+
+impl SharedTPS6572x {
+    pub const fn new() -> Self {
+        Self {
+            address: TPS_ADDRESS,
+        }
+    }
+
+    /// Uses another address than [`TPS_ADDRESS`]
+    pub const fn with_address(mut self, address: SevenBitAddress) -> Self {
+        self.address = address;
+        self
+    }
+
+    /// Address of the chip on the bus
+    pub const fn address(&self) -> SevenBitAddress {
+        self.address
+    }
+
+    /// Driver borrowing the bus, dropping it frees the bus
+    pub fn attach<'a, I2C, I2CE>(&self, i2c: &'a mut I2C) -> TPS6572x<&'a mut I2C, I2CE>
+    where
+        I2C: I2c<SevenBitAddress, Error = I2CE>,
+    {
+        TPS6572x {
+            i2c,
+            address: self.address,
+        }
+    }
+
+    pub fn write_register<R, I2C, I2CE>(
+        &self,
+        i2c: &mut I2C,
+        register: R,
+    ) -> Result<(), Error<I2CE>>
+    where
+        R: WritableRegister,
+        u8: From<R>,
+        I2C: I2c<SevenBitAddress, Error = I2CE>,
+        I2CE: I2cError,
+    {
+        self.attach(i2c).write_register(register)
+    }
+
+    pub fn read_register<R, I2C, I2CE>(&self, i2c: &mut I2C) -> Result<R, Error<I2CE>>
+    where
+        R: Register + From<u8>,
+        I2C: I2c<SevenBitAddress, Error = I2CE>,
+        I2CE: I2cError,
+    {
+        self.attach(i2c).read_register()
+    }
+
+    pub fn edit_register<R, F, I2C, I2CE>(&self, i2c: &mut I2C, f: F) -> Result<(), Error<I2CE>>
+    where
+        F: FnOnce(R) -> R,
+        R: WritableRegister + From<u8>,
+        u8: From<R>,
+        I2C: I2c<SevenBitAddress, Error = I2CE>,
+        I2CE: I2cError,
+    {
+        self.attach(i2c).edit_register(f)
+    }
+}
+
+impl Default for SharedTPS6572x {
+    fn default() -> Self {
+        Self::new()
+    }
+}
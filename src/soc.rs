@@ -0,0 +1,213 @@
+use crate::charger::ChargePhase;
+
+/// Open circuit voltage curve of a battery
+///
+/// Points of voltage in millivolts and state of charge in percent, sorted by
+/// increasing voltage. The state of charge usually increases with the voltage, a
+/// decreasing one is interpolated downwards.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct OcvTable<'a> {
+    points: &'a [(u16, u8)],
+}
+
+impl<'a> OcvTable<'a> {
+    /// Typical curve of a single cell lithium polymer battery
+    pub const LIPO: OcvTable<'static> = OcvTable::new(&[
+        (3000, 0),
+        (3450, 5),
+        (3680, 10),
+        (3740, 20),
+        (3770, 30),
+        (3790, 40),
+        (3820, 50),
+        (3870, 60),
+        (3920, 70),
+        (3980, 80),
+        (4060, 90),
+        (4200, 100),
+    ]);
+
+    pub const fn new(points: &'a [(u16, u8)]) -> Self {
+        Self { points }
+    }
+
+    /// State of charge at the open circuit voltage, interpolated and clamped to the curve
+    pub fn soc(&self, millivolts: u16) -> u8 {
+        let (first, last) = match (self.points.first(), self.points.last()) {
+            (Some(first), Some(last)) => (*first, *last),
+            _ => return 0,
+        };
+        if millivolts <= first.0 {
+            return first.1;
+        }
+
+        self.points
+            .windows(2)
+            .find(|w| millivolts <= w[1].0)
+            .map(|w| {
+                let ((v0, s0), (v1, s1)) = (w[0], w[1]);
+                let span = (i32::from(v1) - i32::from(v0)).max(1);
+                let delta = (i32::from(millivolts) - i32::from(v0)).clamp(0, span)
+                    * (i32::from(s1) - i32::from(s0))
+                    / span;
+                (i32::from(s0) + delta) as u8
+            })
+            .unwrap_or(last.1)
+    }
+}
+
+/// Approximate state of charge from the battery voltage and the charge phase
+///
+/// While charging, the battery voltage is above the open circuit voltage, so an offset
+/// is removed before the lookup. The estimate is filtered with an exponential moving
+/// average, each sample having a weight of `1 / 2^filter_shift`.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct SocEstimator<'a> {
+    table: OcvTable<'a>,
+    charging_offset_mv: u16,
+    filter_shift: u8,
+
+    /// Filtered estimate, in 1/256 %
+    estimate: Option<u32>,
+}
+
+impl<'a> SocEstimator<'a> {
+    pub const fn new(table: OcvTable<'a>) -> Self {
+        Self {
+            table,
+            charging_offset_mv: 100,
+            filter_shift: 3,
+            estimate: None,
+        }
+    }
+
+    /// Voltage above the open circuit voltage while charging
+    pub const fn with_charging_offset(mut self, charging_offset_mv: u16) -> Self {
+        self.charging_offset_mv = charging_offset_mv;
+        self
+    }
+
+    /// Weight of the samples in the filter, `0` disables the filter
+    pub const fn with_filter_shift(mut self, filter_shift: u8) -> Self {
+        self.filter_shift = if filter_shift > 8 { 8 } else { filter_shift };
+        self
+    }
+
+    /// Updates with the battery voltage, returning the state of charge in percent
+    pub fn update(&mut self, battery_mv: u16, phase: ChargePhase) -> u8 {
+        let ocv = if phase.is_charging() {
+            battery_mv.saturating_sub(self.charging_offset_mv)
+        } else {
+            battery_mv
+        };
+        let sample = u32::from(self.table.soc(ocv)) << 8;
+
+        let estimate = match self.estimate {
+            Some(estimate) if sample >= estimate => {
+                estimate + ((sample - estimate) >> self.filter_shift)
+            }
+            Some(estimate) => estimate - ((estimate - sample) >> self.filter_shift),
+            None => sample,
+        };
+        self.estimate = Some(estimate);
+        Self::percent(estimate)
+    }
+
+    /// Last estimate, in percent
+    pub fn soc(&self) -> Option<u8> {
+        self.estimate.map(Self::percent)
+    }
+
+    /// Forgets the filtered estimate, e.g. after swapping the battery
+    pub fn reset(&mut self) {
+        self.estimate = None;
+    }
+
+    fn percent(estimate: u32) -> u8 {
+        ((estimate + 128) >> 8).min(100) as u8
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn soc_clamps_to_the_table() {
+        assert_eq!(OcvTable::LIPO.soc(0), 0);
+        assert_eq!(OcvTable::LIPO.soc(3000), 0);
+        assert_eq!(OcvTable::LIPO.soc(4200), 100);
+        assert_eq!(OcvTable::LIPO.soc(u16::MAX), 100);
+        assert_eq!(OcvTable::new(&[]).soc(3800), 0);
+    }
+
+    #[test]
+    fn soc_interpolates_between_points() {
+        assert_eq!(OcvTable::LIPO.soc(3820), 50);
+        assert_eq!(OcvTable::LIPO.soc(3845), 55);
+        assert_eq!(OcvTable::LIPO.soc(3225), 2);
+        assert_eq!(OcvTable::LIPO.soc(4130), 95);
+    }
+
+    #[test]
+    fn soc_of_a_decreasing_table() {
+        let table = OcvTable::new(&[(3000, 0), (3500, 80), (4000, 60), (4200, 100)]);
+        assert_eq!(table.soc(3500), 80);
+        assert_eq!(table.soc(3750), 70);
+        assert_eq!(table.soc(4000), 60);
+        assert_eq!(table.soc(4100), 80);
+    }
+
+    #[test]
+    fn soc_of_unsorted_voltages_stays_in_the_segment() {
+        let table = OcvTable::new(&[(3000, 0), (4000, 100), (3500, 50), (4200, 100)]);
+        assert_eq!(table.soc(3999), 99);
+        assert_eq!(table.soc(4100), 92);
+    }
+
+    #[test]
+    fn charging_offset_is_removed_while_charging() {
+        let mut charging = SocEstimator::new(OcvTable::LIPO);
+        assert_eq!(charging.update(3920, ChargePhase::FastCharge), 50);
+
+        let mut precharging = SocEstimator::new(OcvTable::LIPO).with_charging_offset(50);
+        assert_eq!(precharging.update(3920, ChargePhase::Precharge), 60);
+
+        let mut idle = SocEstimator::new(OcvTable::LIPO);
+        assert_eq!(idle.update(3920, ChargePhase::Idle), 70);
+    }
+
+    #[test]
+    fn filter_converges() {
+        let mut estimator = SocEstimator::new(OcvTable::LIPO);
+        assert_eq!(estimator.update(3820, ChargePhase::Idle), 50);
+
+        let first = estimator.update(3920, ChargePhase::Idle);
+        assert!(first > 50 && first < 70);
+        for _ in 0..100 {
+            estimator.update(3920, ChargePhase::Idle);
+        }
+        assert_eq!(estimator.soc(), Some(70));
+
+        for _ in 0..100 {
+            estimator.update(3820, ChargePhase::Idle);
+        }
+        assert_eq!(estimator.soc(), Some(50));
+
+        let mut unfiltered = SocEstimator::new(OcvTable::LIPO).with_filter_shift(0);
+        unfiltered.update(3820, ChargePhase::Idle);
+        assert_eq!(unfiltered.update(3920, ChargePhase::Idle), 70);
+    }
+
+    #[test]
+    fn reset_forgets_the_estimate() {
+        let mut estimator = SocEstimator::new(OcvTable::LIPO);
+        assert_eq!(estimator.soc(), None);
+        estimator.update(3820, ChargePhase::Idle);
+        estimator.reset();
+        assert_eq!(estimator.soc(), None);
+        assert_eq!(estimator.update(4200, ChargePhase::Idle), 100);
+    }
+}
@@ -0,0 +1,247 @@
+use embedded_hal::i2c::{blocking::I2c, Error as I2cError, SevenBitAddress};
+
+use crate::{error::Error, registers::*, TPS6572x};
+
+/// Battery temperature window in which charging is allowed, in °C
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct TemperatureWindow {
+    pub min_c: i16,
+    pub max_c: i16,
+}
+
+impl From<BatteryTemperatureShift> for TemperatureWindow {
+    fn from(shift: BatteryTemperatureShift) -> Self {
+        let min_c = match shift {
+            BatteryTemperatureShift::C0_45 => 0,
+            BatteryTemperatureShift::C5_50 => 5,
+            BatteryTemperatureShift::C10_55 => 10,
+            BatteryTemperatureShift::C15_60 => 15,
+        };
+        Self {
+            min_c,
+            max_c: min_c + 45,
+        }
+    }
+}
+
+impl TemperatureWindow {
+    /// Window of the temperatures, fails with [`Error::OutOfRange`] if `min_c` is not below `max_c`
+    pub(crate) fn new<E>(min_c: i16, max_c: i16) -> Result<Self, Error<E>> {
+        if min_c >= max_c {
+            return Err(Error::OutOfRange {
+                value: min_c.into(),
+                min: i16::MIN.into(),
+                max: i32::from(max_c) - 1,
+            });
+        }
+        Ok(Self { min_c, max_c })
+    }
+
+    /// Temperature shift with the window closest to this one
+    pub fn nearest_shift(&self) -> BatteryTemperatureShift {
+        let distance = |shift: &BatteryTemperatureShift| {
+            let window = TemperatureWindow::from(*shift);
+            (i32::from(window.min_c) - i32::from(self.min_c)).abs()
+                + (i32::from(window.max_c) - i32::from(self.max_c)).abs()
+        };
+        [
+            BatteryTemperatureShift::C0_45,
+            BatteryTemperatureShift::C5_50,
+            BatteryTemperatureShift::C10_55,
+            BatteryTemperatureShift::C15_60,
+        ]
+        .into_iter()
+        .min_by_key(distance)
+        .unwrap_or(BatteryTemperatureShift::C0_45)
+    }
+
+    /// Whether the temperature is in the window
+    pub fn contains(&self, temperature_c: i16) -> bool {
+        (self.min_c..=self.max_c).contains(&temperature_c)
+    }
+}
+
+/// Resistance to temperature curve of an NTC thermistor
+///
+/// Points of temperature in °C and resistance relative to the resistance at 25°C
+/// in thousandths, sorted by increasing temperature and decreasing resistance.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct NtcCurve<'a> {
+    points: &'a [(i16, u32)],
+}
+
+impl<'a> NtcCurve<'a> {
+    /// Curve of a thermistor with a beta of 3380K, from -20°C to 80°C
+    pub const BETA_3380: NtcCurve<'static> = NtcCurve::new(&[
+        (-20, 7502),
+        (-15, 5793),
+        (-10, 4517),
+        (-5, 3555),
+        (0, 2822),
+        (5, 2259),
+        (10, 1823),
+        (15, 1482),
+        (20, 1213),
+        (25, 1000),
+        (30, 829),
+        (35, 692),
+        (40, 581),
+        (45, 490),
+        (50, 416),
+        (55, 355),
+        (60, 304),
+        (65, 262),
+        (70, 226),
+        (75, 196),
+        (80, 171),
+    ]);
+
+    pub const fn new(points: &'a [(i16, u32)]) -> Self {
+        Self { points }
+    }
+
+    /// Temperature of the thermistor, interpolated and clamped to the curve
+    pub fn temperature(&self, resistance_ohms: u32, ntc: NTC) -> i16 {
+        let nominal: u64 = match ntc {
+            NTC::K10 => 10_000,
+            NTC::K100 => 100_000,
+        };
+        let ratio = (u64::from(resistance_ohms) * 1000 / nominal) as u32;
+
+        let (first, last) = match (self.points.first(), self.points.last()) {
+            (Some(first), Some(last)) => (*first, *last),
+            _ => return 25,
+        };
+        if ratio >= first.1 {
+            return first.0;
+        }
+
+        self.points
+            .windows(2)
+            .find(|w| ratio >= w[1].1)
+            .map(|w| {
+                let ((t0, r0), (t1, r1)) = (w[0], w[1]);
+                let span = (r0 - r1) as i32;
+                t0 + ((r0 - ratio) as i32 * (t1 - t0) as i32 / span) as i16
+            })
+            .unwrap_or(last.0)
+    }
+}
+
+/// Thermal state of the charger
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct ThermalStatus {
+    /// Battery below the charge temperature window, TS_COLD
+    pub too_cold: bool,
+
+    /// Battery above the charge temperature window, TS_HOT
+    pub too_hot: bool,
+
+    /// Charge current reduced by the thermal regulation loop, CH_THLOOP
+    pub thermal_loop_active: bool,
+}
+
+impl ThermalStatus {
+    /// Whether the battery temperature allows charging
+    pub fn charging_allowed(&self) -> bool {
+        !self.too_cold && !self.too_hot
+    }
+}
+
+impl From<ChargerStatus> for ThermalStatus {
+    fn from(status: ChargerStatus) -> Self {
+        Self {
+            too_cold: status.temp_low(),
+            too_hot: status.temp_high(),
+            thermal_loop_active: status.thermal_loop_active(),
+        }
+    }
+}
+
+impl<I2C, I2CE> TPS6572x<I2C, I2CE>
+where
+    I2C: I2c<SevenBitAddress, Error = I2CE>,
+    I2CE: I2cError,
+{
+    /// Battery temperature window in which charging is allowed
+    pub fn charge_temperature_window(&mut self) -> Result<TemperatureWindow, Error<I2CE>> {
+        Ok(self
+            .read_register::<ChargerConfig3>()?
+            .batt_temperature_shift()
+            .into())
+    }
+
+    /// Selects the supported window nearest to the temperatures, returning the actual window
+    ///
+    /// Fails with [`Error::OutOfRange`] if `min_c` is not below `max_c`.
+    pub fn set_charge_temperature_window(
+        &mut self,
+        min_c: i16,
+        max_c: i16,
+    ) -> Result<TemperatureWindow, Error<I2CE>> {
+        let shift = TemperatureWindow::new(min_c, max_c)?.nearest_shift();
+        self.edit_register(|r: ChargerConfig3| r.with_batt_temperature_shift(shift))?;
+        Ok(shift.into())
+    }
+
+    /// Resistance at 25°C of the battery thermistor
+    pub fn ntc_sensor(&mut self) -> Result<NTC, Error<I2CE>> {
+        Ok(self.read_register::<ChargerConfig2>()?.sensor_resistance())
+    }
+
+    /// Sets the resistance at 25°C of the battery thermistor
+    pub fn set_ntc_sensor(&mut self, ntc: NTC) -> Result<(), Error<I2CE>> {
+        self.edit_register(|r: ChargerConfig2| r.with_sensor_resistance(ntc))
+    }
+
+    /// Thermal state of the charger
+    pub fn thermal_status(&mut self) -> Result<ThermalStatus, Error<I2CE>> {
+        Ok(self.read_register::<ChargerStatus>()?.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nearest_shift() {
+        let shift = |min_c, max_c| TemperatureWindow { min_c, max_c }.nearest_shift();
+        assert_eq!(shift(0, 45), BatteryTemperatureShift::C0_45);
+        assert_eq!(shift(6, 49), BatteryTemperatureShift::C5_50);
+        assert_eq!(shift(15, 60), BatteryTemperatureShift::C15_60);
+        assert_eq!(shift(30, 80), BatteryTemperatureShift::C15_60);
+    }
+
+    #[test]
+    fn nearest_shift_of_extreme_windows() {
+        let shift = |min_c, max_c| TemperatureWindow { min_c, max_c }.nearest_shift();
+        assert_eq!(shift(i16::MIN, 0), BatteryTemperatureShift::C0_45);
+        assert_eq!(
+            shift(i16::MAX - 1, i16::MAX),
+            BatteryTemperatureShift::C15_60
+        );
+        assert_eq!(shift(-20000, 20000), BatteryTemperatureShift::C0_45);
+        assert_eq!(shift(i16::MIN, i16::MAX), BatteryTemperatureShift::C0_45);
+        assert_eq!(shift(i16::MAX, i16::MIN), BatteryTemperatureShift::C0_45);
+    }
+
+    #[test]
+    fn empty_window_is_out_of_range() {
+        let out_of_range = |min_c, max_c| match TemperatureWindow::new::<()>(min_c, max_c) {
+            Err(Error::OutOfRange { value, min, max }) => Some((value, min, max)),
+            _ => None,
+        };
+        assert_eq!(out_of_range(10, 10), Some((10, -32768, 9)));
+        assert_eq!(out_of_range(20, 10), Some((20, -32768, 9)));
+        assert_eq!(
+            out_of_range(i16::MIN, i16::MIN),
+            Some((-32768, -32768, -32769))
+        );
+        assert_eq!(out_of_range(9, 10), None);
+        assert!(TemperatureWindow::new::<()>(i16::MIN, i16::MAX).is_ok());
+    }
+}
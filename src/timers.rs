@@ -0,0 +1,138 @@
+use core::time::Duration;
+use embedded_hal::i2c::{blocking::I2c, Error as I2cError, SevenBitAddress};
+
+use crate::{error::Error, registers::*, TPS6572x};
+
+/// Charge timers and thermal regulation of the charger
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct ChargeTimers {
+    /// Precharge timer, 30 or 60 minutes
+    pub precharge: Duration,
+
+    /// Fast charge safety timer, 4, 5, 6 or 8 hours
+    pub safety: Duration,
+
+    /// Slows the timers down while the charge current is reduced, DYN_TMR
+    pub dynamic_timer: bool,
+
+    /// Reduces the charge current when the chip is too hot, TH_LOOP
+    pub thermal_regulation: bool,
+}
+
+impl ChargeTimers {
+    /// Register values of the timers, the error being in seconds if not supported
+    ///
+    /// A duration with a fraction of a second is rounded up, to never be reported as a
+    /// supported one.
+    pub(crate) fn timers<E>(&self) -> Result<(PreChargeTimer, SafetyChargeTimer), Error<E>> {
+        let seconds = |d: Duration| {
+            let rounded = d.as_secs() + u64::from(d.subsec_nanos() > 0);
+            i32::try_from(rounded).unwrap_or(i32::MAX)
+        };
+
+        let precharge = PreChargeTimer::from_duration(self.precharge).ok_or_else(|| {
+            Error::unsupported(
+                seconds(self.precharge),
+                seconds(PreChargeTimer::Min30.duration()),
+                seconds(PreChargeTimer::Min60.duration()),
+            )
+        })?;
+        let safety = SafetyChargeTimer::from_duration(self.safety).ok_or_else(|| {
+            Error::unsupported(
+                seconds(self.safety),
+                seconds(SafetyChargeTimer::Hour4.duration()),
+                seconds(SafetyChargeTimer::Hour8.duration()),
+            )
+        })?;
+        Ok((precharge, safety))
+    }
+}
+
+impl<I2C, I2CE> TPS6572x<I2C, I2CE>
+where
+    I2C: I2c<SevenBitAddress, Error = I2CE>,
+    I2CE: I2cError,
+{
+    /// Charge timers and thermal regulation
+    pub fn charge_timers(&mut self) -> Result<ChargeTimers, Error<I2CE>> {
+        let config0 = self.read_register::<ChargerConfig0>()?;
+        let config2 = self.read_register::<ChargerConfig2>()?;
+        Ok(ChargeTimers {
+            precharge: config2.pre_charge_timer().duration(),
+            safety: config2.safety_charge_timer().duration(),
+            dynamic_timer: config0.dynamic_timer(),
+            thermal_regulation: config0.thermal_loop(),
+        })
+    }
+
+    /// Sets the charge timers and thermal regulation
+    ///
+    /// The durations are validated before any register is written.
+    pub fn set_charge_timers(&mut self, timers: &ChargeTimers) -> Result<(), Error<I2CE>> {
+        let (precharge, safety) = timers.timers()?;
+        let config0 = self.read_register::<ChargerConfig0>()?;
+        let config2 = self.read_register::<ChargerConfig2>()?;
+
+        self.write_register(
+            config2
+                .with_pre_charge_timer(precharge)
+                .with_safety_charge_timer(safety),
+        )?;
+        self.write_register(
+            config0
+                .with_dynamic_timer(timers.dynamic_timer)
+                .with_thermal_loop(timers.thermal_regulation),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(precharge: Duration, safety: Duration) -> Option<(i32, i32, i32)> {
+        let timers = ChargeTimers {
+            precharge,
+            safety,
+            dynamic_timer: false,
+            thermal_regulation: false,
+        };
+        match timers.timers::<()>() {
+            Err(Error::UnsupportedValue { value }) => Some((value, 0, 0)),
+            Err(Error::OutOfRange { value, min, max }) => Some((value, min, max)),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn unsupported_timers_are_reported_in_seconds() {
+        let hours = |h: u64| Duration::from_secs(h * 60 * 60);
+        assert_eq!(error(Duration::from_secs(30 * 60), hours(5)), None);
+        assert_eq!(
+            error(Duration::from_secs(30 * 60 + 30), hours(5)),
+            Some((1830, 0, 0))
+        );
+        assert_eq!(
+            error(Duration::from_secs(61 * 60), hours(5)),
+            Some((3660, 1800, 3600))
+        );
+        assert_eq!(
+            error(Duration::from_secs(30 * 60), hours(7)),
+            Some((25200, 0, 0))
+        );
+        assert_eq!(
+            error(Duration::from_secs(60 * 60), hours(9)),
+            Some((32400, 14400, 28800))
+        );
+    }
+
+    #[test]
+    fn fractions_of_a_second_are_never_reported_as_supported() {
+        let precharge = Duration::from_secs(30 * 60) + Duration::from_millis(1);
+        assert_eq!(
+            error(precharge, Duration::from_secs(5 * 60 * 60)),
+            Some((1801, 0, 0))
+        );
+    }
+}
@@ -0,0 +1,82 @@
+use embedded_hal::i2c::{blocking::I2c, Error as I2cError, SevenBitAddress};
+
+use crate::{
+    error::Error,
+    registers::{AcInputCurrent, ChargerConfig0},
+    TPS6572x,
+};
+
+/// State of the USB input, as negotiated by the USB stack
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum UsbState {
+    /// Attached but not configured by the host
+    Unconfigured,
+
+    /// Configured by the host, with the current of the configuration in mA
+    Configured { max_ma: u16 },
+
+    /// Suspended by the host
+    Suspended,
+
+    /// Dedicated charging port detected with BC1.2
+    DedicatedCharger,
+}
+
+/// Input current for each [`UsbState`]
+///
+/// A configuration of 500 mA or more uses the 500 mA limit, any other USB state is
+/// limited to 100 mA, and suspend puts the input in standby.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct UsbInputPolicy {
+    /// Input current before the host configures the device
+    pub unconfigured: AcInputCurrent,
+
+    /// Input current from a dedicated charger, without DPPM by default since it is not
+    /// limited by a host
+    pub dedicated_charger: AcInputCurrent,
+}
+
+impl UsbInputPolicy {
+    pub const fn new() -> Self {
+        Self {
+            unconfigured: AcInputCurrent::MA100ddpm,
+            dedicated_charger: AcInputCurrent::MA500,
+        }
+    }
+
+    /// Input current of the USB state
+    pub fn input_current(&self, state: UsbState) -> AcInputCurrent {
+        match state {
+            UsbState::Unconfigured => self.unconfigured,
+            UsbState::Configured { max_ma } if max_ma >= 500 => AcInputCurrent::MA500ddpm,
+            UsbState::Configured { .. } => AcInputCurrent::MA100ddpm,
+            UsbState::Suspended => AcInputCurrent::UsbSuspend,
+            UsbState::DedicatedCharger => self.dedicated_charger,
+        }
+    }
+}
+
+impl Default for UsbInputPolicy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<I2C, I2CE> TPS6572x<I2C, I2CE>
+where
+    I2C: I2c<SevenBitAddress, Error = I2CE>,
+    I2CE: I2cError,
+{
+    /// Sets the input current for the USB state, returning it
+    pub fn apply_usb_state(
+        &mut self,
+        policy: &UsbInputPolicy,
+        state: UsbState,
+    ) -> Result<AcInputCurrent, Error<I2CE>> {
+        let current = policy.input_current(state);
+        self.edit_register(|r: ChargerConfig0| r.with_ac_input_current(current))?;
+        Ok(current)
+    }
+}